@@ -1,30 +1,35 @@
 
 use std::fmt;
+use std::str::FromStr;
 use crate::enums::*;
 
 // encoding: tens place = color, units place map: 1 1 1 2 2 3 3 4 4 5
+// rainbow cards, only in the rainbow variant, are 50 to 59
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Card (pub u8);
 
 impl Card {
     pub fn new(encoded: u8) -> Self {
+        // fail where the bad card is made, not later in get_color/get_value. unchecked in release builds
+        debug_assert!(encoded < 60, "Invalid card encoding {}", encoded);
         Card(encoded)
     }
 
-    pub fn from_value_color_idx(value: usize, color_idx: usize) -> Self {
-        Card::new((10*value + 2*(color_idx+1)) as u8) // 2*(color_idx+1) happens to align with the right units place
+    // value_idx 0..5 stands for the values 1 to 5, gives the first copy of that card
+    pub fn from_value_color_idx(value_idx: usize, color_idx: usize) -> Self {
+        const FIRST_COPY: [usize; 5] = [0, 3, 5, 7, 9];
+        Card::new((10*color_idx + FIRST_COPY[value_idx]) as u8)
+    }
+
+    // the first copy of the card with this color and value
+    pub fn from_color_value(color: Color, value: u8) -> Self {
+        debug_assert!((1..=5).contains(&value), "Invalid card value {}", value);
+        Card::from_value_color_idx(value as usize - 1, color.index())
     }
 
     pub fn get_color(&self) -> Color {
-        match self.0 / 10 {
-            0 => Color::Red,
-            1 => Color::Green,
-            2 => Color::Blue,
-            3 => Color::Yellow,
-            4 => Color::White,
-            _ => panic!("Invalid card color"), // panic for invalid color, should not happen
-        }
+        Color::from_index((self.0 / 10) as usize)
     }
 
     pub fn get_value(&self) -> u8 {
@@ -37,19 +42,94 @@ impl Card {
             _ => panic!("Invalid card value"), // panic for invalid value, should not happen
         }
     }
+
+    // true for two copies of the same kind, == compares the physical cards
+    pub fn same_kind(&self, other: &Card) -> bool {
+        self.get_color() == other.get_color() && self.get_value() == other.get_value()
+    }
+
+    // one card of every kind in the standard deck, the first copy of each: 25 cards, red 1 first
+    pub fn all_types() -> impl Iterator<Item = Card> {
+        Card::all_types_for(GameVariant::Standard)
+    }
+
+    // the same with the rainbow cards when the variant has them
+    pub fn all_types_for(variant: GameVariant) -> impl Iterator<Item = Card> {
+        variant.colors().iter()
+            .flat_map(|&color| (1..=5).map(move |value| Card::from_color_value(color, value)))
+    }
+
+    // how many copies of this kind of card a deck holds: three 1s, two each of 2, 3 and 4, one 5
+    pub fn max_copies(&self) -> u8 {
+        match self.get_value() {
+            1 => 3,
+            2..=4 => 2,
+            _ => 1,
+        }
+    }
+
+    // plain notation without terminal colors, like R3. parses back to the same kind of card
+    pub fn to_code(self) -> String {
+        format!("{}{}", self.get_color().letter(), self.get_value())
+    }
+}
+
+// parses the notation of to_code, like "R3" or "w5", into the first copy of that card
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let (Some(letter), Some(digit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(format!("'{}' is not a card, expected a color letter and a value like R3", s));
+        };
+        let color = GameVariant::Rainbow.colors().iter()
+            .find(|color| color.letter() == letter.to_ascii_uppercase())
+            .ok_or_else(|| format!("'{}' is not a color letter", letter))?;
+        let value = digit.to_digit(10)
+            .filter(|value| (1..=5).contains(value))
+            .ok_or_else(|| format!("'{}' is not a card value from 1 to 5", digit))?;
+        Ok(Card::from_color_value(*color, value as u8))
+    }
 }
 
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (start, end) = match self.get_color() {
-            Color::Red => ("\x1b[31m", "\x1b[0m red"),
-            Color::Green => ("\x1b[32m", "\x1b[0m green"),
-            Color::Blue => ("\x1b[34m", "\x1b[0m blue"),
-            Color::Yellow => ("\x1b[33m", "\x1b[0m yellow"),
-            Color::White => ("\x1b[37m", "\x1b[0m white"),
+        let end = match self.get_color() {
+            Color::Red => "\x1b[0m red",
+            Color::Green => "\x1b[0m green",
+            Color::Blue => "\x1b[0m blue",
+            Color::Yellow => "\x1b[0m yellow",
+            Color::White => "\x1b[0m white",
+            Color::Rainbow => "\x1b[0m rainbow",
         };
 
-        write!(f, "{}[{}]{}", start, self.get_value(), end)
+        write!(f, "{}[{}]{}", self.get_color().ansi_code(), self.get_value(), end)
+    }
+}
+
+// Renders a set of cards grouped by color and sorted by value, like "R: 1 1 3, G: 2"
+pub struct CardGroups<'a>(pub &'a [Card]);
+
+impl fmt::Display for CardGroups<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first_group = true;
+        for &color in GameVariant::Rainbow.colors() {
+            let mut values: Vec<u8> = self.0.iter()
+                .filter(|card| card.get_color() == color)
+                .map(|card| card.get_value())
+                .collect();
+            if values.is_empty() { continue; }
+            values.sort();
+            if !first_group { write!(f, ", ")?; }
+            first_group = false;
+            write!(f, "{}:", color.letter())?;
+            for value in values {
+                write!(f, " {}", value)?;
+            }
+        }
+        if first_group { write!(f, "-")?; }
+        Ok(())
     }
 }
 
@@ -65,4 +145,33 @@ impl fmt::Display for Card {
 
 //         write!(f, "{}[{}]{}", start, self.get_value(), end)
 //     }
-// }
\ No newline at end of file
+// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_render_by_color_and_value() {
+        let cards = [Card::new(13), Card::new(5), Card::new(0), Card::new(1)];
+        assert_eq!(CardGroups(&cards).to_string(), "R: 1 1 3, G: 2");
+        assert_eq!(CardGroups(&[]).to_string(), "-");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Invalid card encoding 60")]
+    fn encoding_past_the_rainbow_cards_panics() {
+        Card::new(60);
+    }
+
+    #[test]
+    fn code_parses_back_to_the_same_kind() {
+        for card in Card::all_types_for(GameVariant::Rainbow) {
+            assert_eq!(card.to_code().parse::<Card>(), Ok(card));
+        }
+        assert_eq!("w5".parse::<Card>(), Ok(Card::new(49)));
+        for bad in ["X3", "R0", "R6", "R33", ""] {
+            assert!(bad.parse::<Card>().is_err(), "{} parsed", bad);
+        }
+    }
+}
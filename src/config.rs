@@ -0,0 +1,59 @@
+use std::env;
+use std::path::PathBuf;
+
+// environment variable that points to the directory holding the strategy config files
+pub const PARAMS_DIR_VAR: &str = "HANABI_PARAMS_DIR";
+
+// finds a strategy config file by looking in $HANABI_PARAMS_DIR, then next to the executable, then in the working directory.
+// If the file exists nowhere, the plain filename is returned so loaders fall back to their defaults as before.
+// Set HANABI_DEBUG to see which path was picked.
+pub fn resolve_config_path(filename: &str) -> String {
+    resolve_in(filename, env::var(PARAMS_DIR_VAR).ok())
+}
+
+// the lookup itself, with the params directory passed in instead of read from the environment
+fn resolve_in(filename: &str, params_dir: Option<String>) -> String {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = params_dir {
+        candidates.push(PathBuf::from(dir).join(filename));
+    }
+    if let Ok(exe) = env::current_exe() && let Some(dir) = exe.parent() {
+        candidates.push(dir.join(filename));
+    }
+    candidates.push(PathBuf::from(filename));
+
+    let resolved = candidates.iter()
+        .find(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|| filename.to_string());
+
+    if env::var_os("HANABI_DEBUG").is_some() {
+        eprintln!("[debug] config {} resolved to {}", filename, resolved);
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // a file in the params directory wins, without one the lookup falls through to the plain filename
+    #[test]
+    fn file_in_params_dir_is_found() {
+        let dir = env::temp_dir().join(format!("hanabi_params_dir_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let filename = "resolve_test_params.txt";
+        let in_dir = dir.join(filename);
+        fs::write(&in_dir, "score_play_sure = 1.0\n").unwrap();
+        let dir_name = dir.to_string_lossy().into_owned();
+
+        let found = resolve_in(filename, Some(dir_name.clone()));
+        fs::remove_file(&in_dir).unwrap();
+        let missing = resolve_in(filename, Some(dir_name));
+        fs::remove_dir(&dir).unwrap();
+
+        assert_eq!(found, in_dir.to_string_lossy());
+        assert_eq!(missing, filename);
+    }
+}
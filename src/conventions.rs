@@ -0,0 +1,123 @@
+use crate::card::Card;
+use crate::decksubset::DeckSubset;
+use crate::enums::{Move, Color, GameVariant};
+use crate::strategy_utils::{apply_hint, chop_index, is_touched, knowledge_after_hint};
+
+// What a hint means beyond the cards it touches, so that the bots using this module understand each other.
+// The hinter only gives hints whose meaning is true for the hand it sees, and the receiver adds the meaning
+// to what it knows about its own hand. Both sides read a hint from the receiver's knowledge before it,
+// which the hinter tracks as well, so they always agree on the meaning. Gemini and ChatGPT use it, but only
+// with a partner that uses it too, anyone else's hints are read for what they say about the cards alone
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintMeaning {
+    Play(usize), // the focused card is playable right now
+    Save(usize), // the focused card is on the chop and has to be kept
+    Info,        // nothing beyond what the hint says
+}
+
+// the card the hint is about: the chop if the hint touches it, otherwise the newest card touched for
+// the first time, or the newest touched card if every touched card was hinted before
+pub fn focus(knowledge: &[DeckSubset], touched: &[usize]) -> Option<usize> {
+    if let Some(chop) = chop_index(knowledge) && touched.contains(&chop) {
+        return Some(chop);
+    }
+    touched.iter().rev()
+        .find(|&&i| !is_touched(&knowledge[i]))
+        .or(touched.last())
+        .copied()
+}
+
+// a value hint on the chop saves the card, any other hint asks to play the focused card,
+// unless the hint itself rules out that the card can be played
+pub fn meaning(hint: &Move, knowledge: &[DeckSubset], touched: &[usize], fireworks: &[u8; 6]) -> HintMeaning {
+    let Some(focus) = focus(knowledge, touched) else { return HintMeaning::Info };
+    if matches!(hint, Move::HintValue(_)) && chop_index(knowledge) == Some(focus) {
+        return HintMeaning::Save(focus);
+    }
+    if knowledge_after_hint(&knowledge[focus], hint).intersect(&playable_cards(fireworks)).is_empty() {
+        HintMeaning::Info
+    } else {
+        HintMeaning::Play(focus)
+    }
+}
+
+// the receiver's knowledge after the hint: touched slots learn the color or value, the others that they
+// don't have it, and a play hint narrows the focused slot down to the cards that are playable now
+pub fn knowledge_after(hint: &Move, knowledge: &[DeckSubset], touched: &[usize], fireworks: &[u8; 6]) -> Vec<DeckSubset> {
+    let touching = match hint {
+        Move::HintColor(color) => DeckSubset::from_color(*color).union(&DeckSubset::from_color(Color::Rainbow)),
+        Move::HintValue(value) => DeckSubset::from_value(*value),
+        _ => DeckSubset::new_empty(),
+    };
+    let mut after: Vec<DeckSubset> = knowledge.iter().enumerate()
+        .map(|(i, k)| if touched.contains(&i) { knowledge_after_hint(k, hint) } else { k.difference(&touching) })
+        .collect();
+    if let HintMeaning::Play(slot) = meaning(hint, knowledge, touched, fireworks) {
+        after[slot] = after[slot].intersect(&playable_cards(fireworks));
+    }
+    after
+}
+
+// true for the strategies that give and read hints through these conventions, wrapped ones included
+pub fn plays_conventions(name: &str) -> bool {
+    matches!(name, "Gemini" | "ChatGPT" | "SafeGemini" | "SafeChatGPT" | "LookaheadGemini")
+}
+
+// knowledge_after if both players agreed on the conventions, otherwise the hint only tells the
+// touched slots that they have the color or value and the others that they don't
+pub fn read_hint(agreed: bool, hint: &Move, knowledge: &[DeckSubset], touched: &[usize], fireworks: &[u8; 6]) -> Vec<DeckSubset> {
+    if agreed {
+        return knowledge_after(hint, knowledge, touched, fireworks);
+    }
+    let mut after = knowledge.to_vec();
+    apply_hint(&mut after, touched, hint);
+    after
+}
+
+// the slots of the hand the hint touches
+pub fn touched_slots(hint: &Move, hand: &[Card]) -> Vec<usize> {
+    (0..hand.len()).filter(|&i| match hint {
+        Move::HintColor(color) => hand[i].get_color().touched_by(*color),
+        Move::HintValue(value) => hand[i].get_value() == *value,
+        _ => false,
+    }).collect()
+}
+
+// true if what the hint means holds for the cards the hinter sees. Hints that are not would make
+// the receiver misplay, so the hinter does not give them
+pub fn is_truthful(hint: &Move, knowledge: &[DeckSubset], hand: &[Card], fireworks: &[u8; 6]) -> bool {
+    match meaning(hint, knowledge, &touched_slots(hint, hand), fireworks) {
+        HintMeaning::Play(slot) => fireworks[hand[slot].get_color().index()] + 1 == hand[slot].get_value(),
+        HintMeaning::Save(_) | HintMeaning::Info => true,
+    }
+}
+
+// every kind of card that can be played on the fireworks right now
+pub fn playable_cards(fireworks: &[u8; 6]) -> DeckSubset {
+    let mut playable = DeckSubset::new_empty();
+    for card_type in Card::all_types_for(GameVariant::Rainbow) {
+        if fireworks[card_type.get_color().index()] + 1 == card_type.get_value() {
+            playable = playable.union(&DeckSubset::from_card_type(&card_type));
+        }
+    }
+    playable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a red hint on the newest card, away from the chop, is a play clue for red 1 on empty fireworks
+    #[test]
+    fn play_clue_is_only_read_with_an_agreed_partner() {
+        let knowledge = vec![DeckSubset::new_full(); 5];
+        let hint = Move::HintColor(Color::Red);
+        let fireworks = [0; 6];
+        let agreed = read_hint(true, &hint, &knowledge, &[4], &fireworks);
+        assert!(agreed[4].is_subset(&playable_cards(&fireworks)));
+        let literal = read_hint(false, &hint, &knowledge, &[4], &fireworks);
+        assert_eq!(literal[4], DeckSubset::from_color(Color::Red));
+        assert!(plays_conventions("SafeGemini") && !plays_conventions("Robert"));
+    }
+}
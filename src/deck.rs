@@ -1,9 +1,47 @@
 use crate::card::Card;
+use crate::enums::{Color, GameVariant};
 
 use std::fmt;
 use rand::seq::SliceRandom;
 use rand::rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
+// number of draws at the bottom of the deck in which a card type counts as buried
+pub const BURIED_DRAWS: usize = 10;
+
+// a named subset of the standard deck, used to benchmark strategies on nonstandard decks
+pub struct DeckComposition {
+    pub name: &'static str,
+    pub keep: fn(&Card) -> bool, // true for the cards that stay in the deck
+}
+
+impl DeckComposition {
+    // highest score possible with this deck: per color the length of the run 1, 2, 3... that is present
+    pub fn max_score(&self) -> u8 {
+        let cards: Vec<Card> = (0..50).map(Card::new).filter(|c| (self.keep)(c)).collect();
+        let mut max_score = 0;
+        for color in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White] {
+            let mut value = 1;
+            while value <= 5 && cards.iter().any(|c| c.get_color() == color && c.get_value() == value) {
+                value += 1;
+            }
+            max_score += value - 1;
+        }
+        max_score
+    }
+}
+
+// reasons why a list of cards is not a deck a game can be played with
+#[cfg(any(test, feature = "scenarios"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckError {
+    InvalidCard(Card), // an encoding that is not part of the deck
+    DuplicateCard(Card), // the same physical card twice, like two white 5s: there is only one, so both are card 49
+    WrongCount { card_type: Card, expected: usize, found: usize }, // copies of a kind missing, like in a deck one card short
+}
+
+#[derive(Clone)]
 pub struct Deck {
     pub cards: Vec<Card>,
 }
@@ -17,10 +55,91 @@ impl Deck {
         }
     }
 
+    // the standard deck plus the ten rainbow cards
+    pub fn new_full_deck_rainbow() -> Self {
+        Deck {
+            cards: (0..60).map(Card::new).collect()
+        }
+    }
+
+    pub fn new_for_variant(variant: GameVariant) -> Self {
+        match variant {
+            GameVariant::Standard => Deck::new_full_deck(),
+            GameVariant::Rainbow => Deck::new_full_deck_rainbow(),
+        }
+    }
+
+    // the cards of the full deck that the composition keeps, unshuffled
+    pub fn new_with_composition(composition: &DeckComposition) -> Self {
+        let mut deck = Deck::new_full_deck();
+        deck.cards.retain(|c| (composition.keep)(c));
+        deck
+    }
+
+    // cards left to draw
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
     pub fn shuffle(&mut self) {
         let mut rng = rng();
         self.cards.shuffle(&mut rng);
     }
+
+    // the same seed always gives the same deck order
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.cards.shuffle(&mut rng);
+    }
+
+    // a deck in exactly this order, the last card is drawn first. the cards must be the full
+    // standard deck, or the full rainbow deck if any rainbow card is among them, in any order.
+    // a card given twice is a DuplicateCard, even for a kind with several copies
+    #[cfg(any(test, feature = "scenarios"))]
+    pub fn from_order(cards: Vec<Card>) -> Result<Deck, DeckError> {
+        let variant = if cards.iter().any(|c| c.get_color() == Color::Rainbow) { GameVariant::Rainbow } else { GameVariant::Standard };
+        let full = Deck::new_for_variant(variant).cards;
+        let mut seen: Vec<Card> = Vec::new();
+        for card in &cards {
+            if !full.contains(card) { return Err(DeckError::InvalidCard(*card)); }
+            if seen.contains(card) { return Err(DeckError::DuplicateCard(*card)); }
+            seen.push(*card);
+        }
+        for card_type in &full {
+            let same_kind = |c: &&Card| c.get_color() == card_type.get_color() && c.get_value() == card_type.get_value();
+            let expected = full.iter().filter(same_kind).count();
+            let found = cards.iter().filter(same_kind).count();
+            if found != expected {
+                return Err(DeckError::WrongCount { card_type: *card_type, expected, found });
+            }
+        }
+        Ok(Deck { cards })
+    }
+
+    // places a card on top of the deck so it is the next one drawn, for staging scenarios.
+    // This bypasses any legality check: the same card may end up twice in the game
+    #[cfg(any(test, feature = "scenarios"))]
+    pub fn push_next(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    // for each color/value the draw positions of its copies (0 = drawn first, the top of the deck is the end of the vec)
+    // together with a flag telling if all copies are within the last BURIED_DRAWS draws
+    pub fn draw_positions(&self) -> Vec<(Color, u8, Vec<usize>, bool)> {
+        let mut result = Vec::new();
+        let buried_from = self.cards.len().saturating_sub(BURIED_DRAWS);
+        for card_type in Card::all_types_for(GameVariant::Rainbow) {
+            let (color, value) = (card_type.get_color(), card_type.get_value());
+            let positions: Vec<usize> = self.cards.iter().rev().enumerate()
+                .filter(|(_, card)| card.get_color() == color && card.get_value() == value)
+                .map(|(position, _)| position)
+                .collect();
+            if positions.is_empty() { continue; }
+            let buried = positions.iter().all(|&p| p >= buried_from);
+            result.push((color, value, positions, buried));
+        }
+        result
+    }
 }
 
 impl fmt::Display for Deck {
@@ -30,4 +149,50 @@ impl fmt::Display for Deck {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the only red 5 sits at the bottom, the white 5 is drawn first
+    #[test]
+    fn critical_card_at_the_bottom_is_buried() {
+        let mut cards = vec![Card::new(9)];
+        cards.extend((0..50).filter(|&code| code != 9).map(Card::new));
+        let deck = Deck { cards };
+        let positions = deck.draw_positions();
+        let find = |color, value| positions.iter().find(|(c, v, _, _)| *c == color && *v == value).expect("card is in the deck");
+
+        assert_eq!(find(Color::Red, 5).2, vec![49]);
+        assert!(find(Color::Red, 5).3);
+        assert_eq!(find(Color::White, 5).2, vec![0]);
+        assert!(!find(Color::White, 5).3);
+    }
+
+    #[test]
+    fn max_score_counts_the_runs_in_the_deck() {
+        let four_colors = DeckComposition { name: "4 colors", keep: |c| c.get_color() != Color::White };
+        assert_eq!(four_colors.max_score(), 20);
+        let no_fives = DeckComposition { name: "No 5s", keep: |c| c.get_value() != 5 };
+        assert_eq!(no_fives.max_score(), 20);
+        // without both red 2s the red firework stops at 1
+        let no_red_twos = DeckComposition { name: "No red 2s", keep: |c| !(c.get_color() == Color::Red && c.get_value() == 2) };
+        assert_eq!(no_red_twos.max_score(), 21);
+    }
+
+    #[test]
+    fn from_order_accepts_only_a_full_deck() {
+        let mut cards: Vec<Card> = (0..50).map(Card::new).collect();
+        cards.reverse();
+        let deck = Deck::from_order(cards.clone()).expect("a permutation of the full deck");
+        assert_eq!(deck.cards, cards);
+
+        let short = cards[1..].to_vec();
+        assert_eq!(Deck::from_order(short).err(), Some(DeckError::WrongCount { card_type: Card::new(49), expected: 1, found: 0 }));
+
+        // the second white 5 can only be card 49 again
+        let mut two_white_fives = cards;
+        two_white_fives[1] = Card::new(49);
+        assert_eq!(Deck::from_order(two_white_fives).err(), Some(DeckError::DuplicateCard(Card::new(49))));
+    }
+}
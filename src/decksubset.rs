@@ -1,16 +1,31 @@
+use std::fmt;
+
 use crate::card::Card;
 use crate::enums::*;
 
 // encoding: tens place = color, units place map: 1 1 1 2 2 3 3 4 4 5
+// every bit is one physical card, so the three red 1s are three different bits. Removing a seen card
+// clears only that copy, the other copies of the same kind stay possible
+
+// every card of every variant, the rainbow suit included
+const ALL_CARDS: u64 = (1u64 << 60) - 1;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct DeckSubset (pub u64);
 
 impl DeckSubset {
+    // the 50 cards of the standard deck
     pub fn new_full() -> Self {
         DeckSubset((1u64 << 50) - 1) 
     }
 
+    pub fn new_full_for(variant: GameVariant) -> Self {
+        match variant {
+            GameVariant::Standard => DeckSubset::new_full(),
+            GameVariant::Rainbow => DeckSubset(ALL_CARDS),
+        }
+    }
+
     pub fn new_empty() -> Self {
         DeckSubset(0)
     }
@@ -22,30 +37,27 @@ impl DeckSubset {
             Color::Blue =>      DeckSubset(0b0000000000000000000000000000000000111111111100000000000000000000),   // Cards 20-29
             Color::Yellow =>    DeckSubset(0b0000000000000000000000001111111111000000000000000000000000000000), // Cards 30-39
             Color::White =>     DeckSubset(0b0000000000000011111111110000000000000000000000000000000000000000),  // Cards 40-49
+            Color::Rainbow =>   DeckSubset(0b0000111111111100000000000000000000000000000000000000000000000000),  // Cards 50-59, rainbow variant only
         }
     }
 
     pub fn from_color_inverted(color: Color) -> Self {
-        let full = Self::new_full().0;
-        let col = Self::from_color(color).0;
-        DeckSubset((!col) & full)
+        Self::from_color(color).complement()
     }
 
     pub fn from_value(value: u8) -> Self {
         match value {
-            1 =>    DeckSubset(0b0000000000000000000001110000000111000000011100000001110000000111),
-            2 =>    DeckSubset(0b0000000000000000000110000000011000000001100000000110000000011000),
-            3 =>    DeckSubset(0b0000000000000000011000000001100000000110000000011000000001100000),
-            4 =>    DeckSubset(0b0000000000000001100000000110000000011000000001100000000110000000),
-            5 =>    DeckSubset(0b0000000000000010000000001000000000100000000010000000001000000000),
+            1 =>    DeckSubset(0b0000000000011100000001110000000111000000011100000001110000000111),
+            2 =>    DeckSubset(0b0000000001100000000110000000011000000001100000000110000000011000),
+            3 =>    DeckSubset(0b0000000110000000011000000001100000000110000000011000000001100000),
+            4 =>    DeckSubset(0b0000011000000001100000000110000000011000000001100000000110000000),
+            5 =>    DeckSubset(0b0000100000000010000000001000000000100000000010000000001000000000),
             _ => panic!("Invalid value for hint: {}", value),
         }
     }
 
     pub fn from_value_inverted(value: u8) -> Self {
-        let full = Self::new_full().0;
-        let val = Self::from_value(value).0;
-        DeckSubset((!val) & full)
+        Self::from_value(value).complement()
     }
 
     pub fn from_card_type(card: &Card) -> Self { // does not give the exact card, but the kind of card: Like blue 1 gives all 3 blue 1's
@@ -61,7 +73,7 @@ impl DeckSubset {
         self.0 &= !(1 << card.0);
     }
 
-    pub fn _add_card(&mut self, card: &Card) {
+    pub fn add_card(&mut self, card: &Card) {
         self.0 |= 1 << card.0;
     }
 
@@ -70,10 +82,116 @@ impl DeckSubset {
     }
 
     pub fn union(&self, other: &DeckSubset) -> DeckSubset {
-        DeckSubset(self.0 | other.0)
+        // masked, so a subset built by hand through the public field can't grow past the 60 cards
+        DeckSubset((self.0 | other.0) & ALL_CARDS)
+    }
+
+    // the cards of this subset that are not in other
+    pub fn difference(&self, other: &DeckSubset) -> DeckSubset {
+        DeckSubset(self.0 & !other.0 & ALL_CARDS)
+    }
+
+    // every card that is not in this subset, out of all 60 so it works for the rainbow variant too.
+    // intersect with a knowledge or new_full() to stay within the standard deck
+    pub fn complement(&self) -> DeckSubset {
+        DeckSubset(!self.0 & ALL_CARDS)
     }
 
     pub fn is_subset(&self, other: &DeckSubset) -> bool {
         (self.0 & other.0) == self.0
     }
-}
\ No newline at end of file
+
+    // number of physical cards in this subset
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // how many copies of the kind of card_type (its color and value) are in this subset
+    pub fn remaining_copies(&self, card_type: Card) -> u32 {
+        self.intersect(&DeckSubset::from_card_type(&card_type)).count()
+    }
+
+    // entropy in bits of a uniformly random card out of this subset
+    pub fn entropy(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { (self.count() as f64).log2() }
+    }
+
+    // all physical cards in this subset, in encoding order
+    pub fn iter_cards(&self) -> impl Iterator<Item = Card> + '_ {
+        (0..60u8).filter(|&i| self.0 & (1 << i) != 0).map(Card::new)
+    }
+
+    // short description of what a slot with this knowledge can be:
+    // R3 for a single card type, R? if only the color is known, ?3 if only the value is known, otherwise the number of possible cards
+    pub fn summary(&self) -> String {
+        let mut cards = self.iter_cards();
+        let Some(first) = cards.next() else { return "(0)".to_string() };
+        let (color, value) = (first.get_color(), first.get_value());
+        let (mut same_color, mut same_value) = (true, true);
+        for card in cards {
+            same_color &= card.get_color() == color;
+            same_value &= card.get_value() == value;
+        }
+        match (same_color, same_value) {
+            (true, true) => format!("{}{}", color.letter(), value),
+            (true, false) => format!("{}?", color.letter()),
+            (false, true) => format!("?{}", value),
+            (false, false) => format!("({})", self.count()),
+        }
+    }
+}
+
+// grid of colors by values, every entry is the number of copies of that card still in the subset, . if none
+impl fmt::Display for DeckSubset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  1 2 3 4 5")?;
+        // the rainbow row only shows up if rainbow cards are possible at all
+        let variant = if self.intersect(&DeckSubset::from_color(Color::Rainbow)).is_empty() { GameVariant::Standard } else { GameVariant::Rainbow };
+        for &color in variant.colors() {
+            write!(f, "{}{}", color.ansi_code(), color.letter())?;
+            for value_idx in 0..5 {
+                let copies = self.remaining_copies(Card::from_value_color_idx(value_idx, color.index()));
+                if copies == 0 {
+                    write!(f, " .")?;
+                } else {
+                    write!(f, " {}", copies)?;
+                }
+            }
+            writeln!(f, "\x1b[0m")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_copies_counts_the_kind_not_the_card() {
+        let mut unseen = DeckSubset::new_full();
+        unseen.remove_card(&Card::new(0));
+        unseen.remove_card(&Card::new(2));
+        // any copy asks for the red 1s, only the middle one is left
+        for code in 0..3 {
+            assert_eq!(unseen.remaining_copies(Card::new(code)), 1);
+        }
+        assert_eq!(unseen.remaining_copies(Card::new(3)), 2);
+        assert_eq!(unseen.remaining_copies(Card::new(10)), 3);
+    }
+
+    #[test]
+    fn summary_names_what_is_known() {
+        let red = DeckSubset::from_color(Color::Red);
+        let threes = DeckSubset::from_value(3);
+        assert_eq!(red.intersect(&threes).summary(), "R3");
+        assert_eq!(red.summary(), "R?");
+        assert_eq!(threes.summary(), "?3");
+        assert_eq!(DeckSubset::new_full().summary(), "(50)");
+        assert_eq!(DeckSubset::new_empty().summary(), "(0)");
+    }
+}
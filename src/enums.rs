@@ -7,6 +7,75 @@ pub enum Color {
     Blue,
     Yellow,
     White,
+    Rainbow, // only in the rainbow variant, touched by every color hint and never hinted itself
+}
+
+impl Color {
+    // single letter used in compact card notation, like R3
+    pub fn letter(self) -> char {
+        match self {
+            Color::Red => 'R',
+            Color::Green => 'G',
+            Color::Blue => 'B',
+            Color::Yellow => 'Y',
+            Color::White => 'W',
+            Color::Rainbow => 'M',
+        }
+    }
+
+    // terminal escape code that switches the text to this color
+    pub fn ansi_code(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Blue => "\x1b[34m",
+            Color::Yellow => "\x1b[33m",
+            Color::White => "\x1b[37m",
+            Color::Rainbow => "\x1b[35m",
+        }
+    }
+
+    // position of the color in the fireworks and in the card encoding, red is 0 and rainbow 5
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_index(index: usize) -> Color {
+        match index {
+            0 => Color::Red,
+            1 => Color::Green,
+            2 => Color::Blue,
+            3 => Color::Yellow,
+            4 => Color::White,
+            5 => Color::Rainbow,
+            _ => panic!("Invalid color index {}", index),
+        }
+    }
+
+    // whether a hint of the given color touches a card of this color
+    pub fn touched_by(self, hint: Color) -> bool {
+        self == hint || self == Color::Rainbow
+    }
+}
+
+// which deck the game is played with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameVariant {
+    Standard,
+    Rainbow, // a sixth suit of rainbow cards, 60 cards in total
+}
+
+impl GameVariant {
+    pub fn colors(self) -> &'static [Color] {
+        match self {
+            GameVariant::Standard => &[Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White],
+            GameVariant::Rainbow => &[Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White, Color::Rainbow],
+        }
+    }
+
+    pub fn max_score(self) -> u8 {
+        5 * self.colors().len() as u8
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,8 +86,20 @@ pub enum Move {
     HintValue(u8),
 }
 
-pub enum MoveResult{
-    Play(bool, Card, Option<Card>), // success, played card, new card if drawn
-    Discard(Card, Option<Card>), // discarded card, new card if drawn
-    Hint(Vec<usize>), //indices of cards hinted, knowledge updates for each card in other player's hand
+// reasons why the game refuses a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    NoHintsRemaining,
+    IndexOutOfBounds, // play or discard of a card the player does not hold
+    HintMatchesNoCard, // hints must point at least one card in the partner's hand
+    RainbowHint, // rainbow is not a color that can be hinted
+    DiscardAtMaxHints, // the strict rules forbid discarding while all hint tokens are held
+}
+
+// what a move turned out to be. the mover gets drawn: None, they don't see their own new card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveResult {
+    Play { success: bool, card: Card, drawn: Option<Card> },
+    Discard { card: Card, drawn: Option<Card> },
+    Hint { indices: Vec<usize> }, // indices of the hinted cards in the receiver's hand
 }
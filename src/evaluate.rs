@@ -0,0 +1,122 @@
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+use crate::game::{Game, GameConfig};
+use crate::enums::GameVariant;
+use crate::player::Player;
+use crate::strategy::Strategy;
+use crate::StrategyFactory;
+
+// Outcome of one game
+pub struct GameStats {
+    pub score: u8,
+    pub seed: Option<u64>,  // the seed the game was dealt from, None for an unseeded deal
+    pub turns: u32,         // number of moves made in the game
+    pub cards_left: usize,  // cards still in the deck when the game ended
+    pub duration: Duration, // wall clock time the game took
+    pub decide_time: [Duration; 2], // time spent in decide_move, for strat1 and strat2
+    pub decide_calls: [u32; 2], // moves decided by strat1 and strat2
+}
+
+// one strategy per seat: strat1 in the even seats, strat2 in the odd ones
+pub fn seat_strategies(strat1: StrategyFactory, strat2: StrategyFactory, number_of_players: usize) -> Vec<Box<dyn Strategy>> {
+    (0..number_of_players)
+        .map(|seat| if seat % 2 == 0 { strat1() } else { strat2() })
+        .collect()
+}
+
+// plays one game with strategies that were built before, one per seat. They are reset before the game and
+// handed back after it, so a thread playing many games builds its strategies once instead of once per game.
+// With a seed the game is reproducible
+pub fn play_game(strategies: &mut Vec<Box<dyn Strategy>>, variant: GameVariant, seed: Option<u64>) -> GameStats {
+    let config = GameConfig { variant, ..GameConfig::for_players(strategies.len()) };
+    let players = strategies.drain(..)
+        .map(|mut strategy| {
+            strategy.reset();
+            Player::new(strategy)
+        })
+        .collect();
+    let start = Instant::now();
+    let mut game = match seed {
+        Some(seed) => Game::new_seeded(players, config, seed),
+        None => Game::new(players, config),
+    };
+    let mut turns = 0;
+    let mut decide_time = [Duration::ZERO; 2];
+    let mut decide_calls = [0; 2];
+
+    loop {
+        if let Some(final_score) = game.game_over() {
+            let stats = GameStats { score: final_score, seed, turns, cards_left: game.deck.remaining(), duration: start.elapsed(), decide_time, decide_calls };
+            strategies.extend(game.players.into_iter().map(|player| player.strategy));
+            return stats;
+        }
+        // the same as game.advance(), with the decision timed for the strategy in the seat
+        let strategy = game.player_to_move % 2;
+        let decide_start = Instant::now();
+        let selected_move = game.decide_move();
+        decide_time[strategy] += decide_start.elapsed();
+        decide_calls[strategy] += 1;
+        game.apply_move(selected_move).expect("bot strategy made an illegal move");
+        turns += 1;
+    }
+}
+
+// plays one game per seed, p1 in the even seats, and returns the stats in the order of the seeds.
+// every game is dealt and every strategy seeded from its seed, so the same seeds always give the same scores.
+// each thread builds its strategies once and reuses them for all of its games
+pub fn evaluate_stats(p1: StrategyFactory, p2: StrategyFactory, number_of_players: usize, variant: GameVariant, seeds: &[u64]) -> Vec<GameStats> {
+    seeds.par_iter()
+        .map_init(|| seat_strategies(p1, p2, number_of_players), |strategies, &seed| play_game(strategies, variant, Some(seed)))
+        .collect()
+}
+
+// the scores of two player games, one per seed, p1 in the first seat
+pub fn evaluate(p1: StrategyFactory, p2: StrategyFactory, seeds: &[u64]) -> Vec<u8> {
+    evaluate_stats(p1, p2, 2, GameVariant::Standard, seeds).iter().map(|stats| stats.score).collect()
+}
+
+// the final score of the game dealt from this seed
+pub fn play_seeded(p1: StrategyFactory, p2: StrategyFactory, seed: u64) -> u8 {
+    play_game(&mut seat_strategies(p1, p2, 2), GameVariant::Standard, Some(seed)).score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::enums::{Move, MoveResult};
+    use crate::game::GameView;
+    use crate::strategies::robert::Robert;
+
+    // discards its oldest card every turn, so the game runs until the deck is out
+    struct AlwaysDiscard;
+
+    impl Strategy for AlwaysDiscard {
+        fn name(&self) -> &'static str { "AlwaysDiscard" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) {}
+        fn decide_move(&mut self, _view: &GameView) -> Move { Move::Discard(0) }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+    }
+
+    // the pooled strategies are reset between games, so each score is the one a fresh pair gets
+    #[test]
+    fn pooled_games_score_like_fresh_ones() {
+        let robert = || -> Box<dyn Strategy> { Box::new(Robert::new()) };
+        let seeds: Vec<u64> = (0..8).collect();
+        let fresh: Vec<u8> = seeds.iter().map(|&seed| play_seeded(&robert, &robert, seed)).collect();
+        assert_eq!(evaluate(&robert, &robert, &seeds), fresh);
+    }
+
+    // 40 discards draw the 40 cards left after the deal, then both players get one last turn
+    #[test]
+    fn discarding_game_reports_every_turn() {
+        let discard = || -> Box<dyn Strategy> { Box::new(AlwaysDiscard) };
+        let stats = play_game(&mut seat_strategies(&discard, &discard, 2), GameVariant::Standard, Some(3));
+        assert_eq!(stats.turns, 42);
+        assert_eq!(stats.decide_calls, [21, 21]);
+        assert_eq!(stats.cards_left, 0);
+        assert_eq!(stats.score, 0);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::strategies::robert::{Robert, Params};
-use crate::player::Player;
-use crate::game::Game;
+use hanabi::strategies::robert::{Robert, Params};
+use hanabi::strategy::Strategy;
+use hanabi::evaluate::evaluate;
+use hanabi::StrategyFactory;
 use rayon::prelude::*;
 use std::fs;
 use rand::prelude::*;
@@ -12,14 +13,38 @@ const GAMES_PER_SPECIES: usize = 2000; // 500 * 2000 = 1,000,000 games per gener
 const ELITISM_COUNT: usize = 50; // The top 50 advance unchanged
 const MUTATION_RATE: f64 = 0.4;  // Probability that a single parameter mutates
 const MUTATION_SCALE: f64 = 0.1; // Standard deviation of the change (10%)
+// the best params of the last finished generation, with the generation and the best score so far
+const CHECKPOINT_FILE: &str = "robert_evolve_checkpoint.txt";
 
-pub fn run_evolution() {
+// with resume, the evolution continues from the checkpoint file if there is one.
+// without opponents every candidate plays against itself, otherwise its fitness is the
+// average score against the opponents, each of them weighted equally
+pub fn run_evolution(resume: bool, opponents: &[(&str, StrategyFactory)]) {
     println!("Starting evolution for Robert strategy...");
+    if !opponents.is_empty() {
+        println!("Opponents: {}", opponents.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", "));
+    }
     println!("Population: {}, Games/Species: {}, Total Games/Gen: {}", 
              POPULATION_SIZE, GAMES_PER_SPECIES, POPULATION_SIZE * GAMES_PER_SPECIES);
 
+    let checkpoint = if resume { load_checkpoint(CHECKPOINT_FILE) } else { None };
+    if resume && checkpoint.is_none() {
+        println!("No checkpoint found in {}, starting from generation 1.", CHECKPOINT_FILE);
+    }
+    let (first_generation, mut best_score_all_time) = match checkpoint {
+        Some((generation, best_score)) => {
+            println!("Resuming after generation {} (best score so far {:.4}).", generation, best_score);
+            (generation + 1, best_score)
+        }
+        None => (1, 0.0),
+    };
+
     // 1. Load initial population (either from file or default + noise)
-    let base_params = Params::load_from_file_or_default("robert_params.txt");
+    let base_params = if checkpoint.is_some() {
+        Params::load_from_file_or_default(CHECKPOINT_FILE)
+    } else {
+        Params::load_from_file_or_default("robert_params.txt")
+    };
     let mut population: Vec<Params> = (0..POPULATION_SIZE).map(|i| {
         if i == 0 {
             base_params.clone() // The original is always part of the first generation
@@ -28,14 +53,12 @@ pub fn run_evolution() {
         }
     }).collect();
 
-    let mut best_score_all_time = 0.0;
-
-    for generation in 1..=GENERATIONS {
+    for generation in first_generation..=GENERATIONS {
         // 2. Evaluation (Parallel)
         // Calculate the average score for each individual
         let mut results: Vec<(usize, f64)> = (0..POPULATION_SIZE).into_par_iter().map(|idx| {
             let params = &population[idx];
-            let score = evaluate_params(params);
+            let score = if opponents.is_empty() { evaluate_params(params) } else { evaluate_params_against(params, opponents) };
             (idx, score)
         }).collect();
 
@@ -58,6 +81,7 @@ pub fn run_evolution() {
             println!("  -> New best parameters found! Saving to robert_params.txt...");
             save_params(&best_params, "robert_params.txt");
         }
+        save_checkpoint(&best_params, generation, best_score_all_time, CHECKPOINT_FILE);
 
         // 4. Selection & Mutation for next generation
         let mut new_population = Vec::with_capacity(POPULATION_SIZE);
@@ -85,23 +109,36 @@ pub fn run_evolution() {
     }
 }
 
+// fresh seeds for every evaluation, so a candidate can't be tuned to a fixed set of deals
+fn random_seeds(count: usize) -> Vec<u64> {
+    (0..count).map(|_| rand::random()).collect()
+}
+
+// the average score of the scores
+fn mean(scores: &[u8]) -> f64 {
+    scores.iter().map(|&score| score as u32).sum::<u32>() as f64 / scores.len() as f64
+}
+
 // Runs the simulations for a specific set of parameters
 fn evaluate_params(params: &Params) -> f64 {
     // Robert plays against himself (clone) to optimize for the strategy
-    let total_score: u32 = (0..GAMES_PER_SPECIES).map(|_| {
-        let p1 = Player::new(Box::new(Robert::new_with_params(*params)));
-        let p2 = Player::new(Box::new(Robert::new_with_params(*params)));
-        let mut game = Game::new(p1, p2);
-        
-        loop {
-            if let Some(score) = game.game_over() {
-                return score as u32;
-            }
-            game.advance();
-        }
-    }).sum();
+    let params = *params;
+    let robert = move || -> Box<dyn Strategy> { Box::new(Robert::new_with_params(params)) };
+    mean(&evaluate(&robert, &robert, &random_seeds(GAMES_PER_SPECIES)))
+}
 
-    total_score as f64 / GAMES_PER_SPECIES as f64
+// the average score of the params over GAMES_PER_SPECIES games split evenly over the opponents,
+// Robert takes the first seat in half of the games
+fn evaluate_params_against(params: &Params, opponents: &[(&str, StrategyFactory)]) -> f64 {
+    let params = *params;
+    let robert = move || -> Box<dyn Strategy> { Box::new(Robert::new_with_params(params)) };
+    let games_per_seat = (GAMES_PER_SPECIES / opponents.len() / 2).max(1);
+    let total: f64 = opponents.iter().map(|(_, opponent)| {
+        let mut scores = evaluate(&robert, *opponent, &random_seeds(games_per_seat));
+        scores.extend(evaluate(*opponent, &robert, &random_seeds(games_per_seat)));
+        mean(&scores)
+    }).sum();
+    total / opponents.len() as f64
 }
 
 fn mutate_params(p: &Params) -> Params {
@@ -148,6 +185,7 @@ fn mutate_params(p: &Params) -> Params {
     mutate_f64!(score_play_make_discardable_weighted_by_partner_knowledge);
     mutate_f64!(score_play_sure);
     mutate_f64!(score_play_focused_hint);
+    mutate_f64!(score_play_finesse);
 
     // DISCARDING
     mutate_i32!(score_discard_exponent_probability);
@@ -170,44 +208,27 @@ fn mutate_params(p: &Params) -> Params {
 }
 
 fn save_params(p: &Params, filename: &str) {
-    let mut content = String::new();
-    
-    macro_rules! write_line {
-        ($field:ident) => {
-            content.push_str(&format!("{}={}\n", stringify!($field), p.$field));
-        };
+    if let Err(e) = fs::write(filename, p.file_content()) {
+        println!("Error writing params: {}", e);
     }
+}
 
-    write_line!(score_play_base);
-    write_line!(score_discard_base);
-    write_line!(score_hint_base);
-    
-    write_line!(score_play_exponent_probability);
-    write_line!(score_play_by_playability_weight);
-    write_line!(score_play_badness_mistake_weight);
-    write_line!(score_play_can_play_5_sure);
-    write_line!(score_play_make_playable);
-    write_line!(score_play_make_playable_weighted_by_partner_knowledge);
-    write_line!(score_play_make_discardable);
-    write_line!(score_play_make_discardable_weighted_by_partner_knowledge);
-    write_line!(score_play_sure);
-    write_line!(score_play_focused_hint);
-
-    write_line!(score_discard_exponent_probability);
-    write_line!(score_discard_value_of_a_hint);
-    write_line!(score_discard_probability_weight);
-    write_line!(score_discard_badness_mistake_weight);
-    write_line!(score_discard_hints_low_weight);
-
-    write_line!(score_hint_focused_hint);
-    write_line!(score_hint_exponent_information_gain);
-    write_line!(score_hint_information_gain);
-    write_line!(score_hint_make_playable);
-    write_line!(score_hint_make_discardable);
-
-    write_line!(score_badness_discard_only_card_left_of_its_kind);
-
-    if let Err(e) = fs::write(filename, content) {
-        println!("Error writing params: {}", e);
+// the params file format plus the generation and best score lines, which the params loader skips as unknown keys.
+// written to a temporary file first, so stopping the evolution while saving never leaves half a checkpoint
+fn save_checkpoint(p: &Params, generation: usize, best_score: f64, filename: &str) {
+    let content = format!("{}generation = {}\nbest_score = {}\n", p.file_content(), generation, best_score);
+    let temporary = format!("{}.tmp", filename);
+    if let Err(e) = fs::write(&temporary, content).and_then(|_| fs::rename(&temporary, filename)) {
+        println!("Error writing checkpoint: {}", e);
     }
 }
+
+// the generation and best score of a checkpoint, None if there is no readable checkpoint
+fn load_checkpoint(filename: &str) -> Option<(usize, f64)> {
+    let content = fs::read_to_string(filename).ok()?;
+    let value = |key: &str| content.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim().to_string());
+    Some((value("generation")?.parse().ok()?, value("best_score")?.parse().ok()?))
+}
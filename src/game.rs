@@ -1,78 +1,464 @@
 use crate::player::Player;
 use crate::deck::Deck;
 use crate::card::Card;
+use crate::decksubset::DeckSubset;
 use crate::enums::*;
+use crate::observer::GameObserver;
 
 pub struct Game {
-    pub players: [Player; 2],
+    pub players: Vec<Player>, // 2 to 5 players, in turn order
     pub deck: Deck,
-    pub fireworks: [u8; 5],
-    pub hints_remaining: u8,
+    config: GameConfig,
+    fireworks: [u8; 6], // one stack per color, the rainbow stack stays empty in the standard game
+    hints_remaining: u8,
     mistakes_made: u8,
     pub player_to_move: usize,
-    deck_empty_countdown: u8,
+    deck_empty_countdown: u8, // moves left once the deck is empty, one for each player, counted down in apply_move
+    discard_pile: Vec<Card>, // discarded and misplayed cards
+    pub hand_knowledge: Vec<Vec<DeckSubset>>, // what each player can know about their own hand from the hints given so far
+    observers: Vec<Box<dyn GameObserver>>, // notified after every move, in the order they were added
+    move_log: Vec<(usize, Move, MoveResult)>, // every move so far with the player who made it and what it did
+    initial_deck: Deck, // the deck as it was before dealing
+    game_over_reported: bool, // the strategies were told the final score
+}
+
+// public state of the game, without any hidden cards. It borrows from the game, so handing one out is free.
+// strategies get one with every decide_move, so their own counters can never drift from the engine
+pub struct GameView<'a> {
+    pub hints_remaining: u8,
+    pub mistakes_made: u8,
+    pub deck_remaining: usize,
+    pub fireworks: [u8; 6],
+    pub discard_pile: &'a [Card], // discarded and misplayed cards, in the order they left the hands
+    pub discard_allowed: bool, // false while the rules forbid discarding with all hint tokens held
+    hand_sizes: [usize; 5], // in turn order, starting with the player to move. Fixed size, so no view allocates
+    number_of_players: usize,
+}
+
+impl GameView<'_> {
+    // the number of cards of the player this many seats after the player to move, 0 is the player to move
+    pub fn hand_size(&self, offset: usize) -> usize {
+        self.hand_sizes[offset % self.number_of_players]
+    }
+}
+
+// the whole game including the hidden cards, only handed to perfect information strategies
+pub struct FullGameState<'a> {
+    pub my_hand: &'a [Card],
+    pub other_hands: Vec<&'a [Card]>, // in turn order, starting with the next player
+    pub deck: &'a [Card], // the next card drawn is the last one
+    pub fireworks: [u8; 6],
+    pub hints_remaining: u8,
+}
+
+// every move the player to move may make, given the hand of the next player, who gets the hints.
+// exactly the moves check_move accepts: play or discard (if the rules allow it) of each own slot, and with a hint token left
+// every color and value hint that touches at least one card
+pub fn legal_moves(view: &GameView, next_hand: &[Card]) -> Vec<Move> {
+    let mut moves: Vec<Move> = Vec::new();
+    for i in 0..view.hand_size(0) {
+        moves.push(Move::Play(i));
+        if view.discard_allowed {
+            moves.push(Move::Discard(i));
+        }
+    }
+    if view.hints_remaining > 0 {
+        for value in 1..=5 {
+            if next_hand.iter().any(|card| card.get_value() == value) {
+                moves.push(Move::HintValue(value));
+            }
+        }
+        for color in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White] {
+            if next_hand.iter().any(|card| card.get_color().touched_by(color)) {
+                moves.push(Move::HintColor(color));
+            }
+        }
+    }
+    moves
+}
+
+// cards per hand: 5 with 2 or 3 players, 4 with 4 or 5 players
+pub fn hand_size(number_of_players: usize) -> usize {
+    if number_of_players <= 3 { 5 } else { 4 }
+}
+
+// a json string literal: the text in quotes, with quotes, backslashes and control characters escaped
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// the rules a game is played with, so house rules can be tried out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameConfig {
+    pub starting_hints: u8, // also the most hint tokens the players can hold
+    pub max_mistakes: u8, // the game is lost on this many mistakes
+    pub hand_size: usize,
+    pub variant: GameVariant,
+    pub discard_at_max_hints: bool, // strict variants forbid a discard while all hint tokens are held
+}
+
+impl GameConfig {
+    // the standard rules for this number of players
+    pub fn for_players(number_of_players: usize) -> Self {
+        GameConfig {
+            starting_hints: 8,
+            max_mistakes: 3,
+            hand_size: hand_size(number_of_players),
+            variant: GameVariant::Standard,
+            discard_at_max_hints: true,
+        }
+    }
 }
 
 impl Game {
-    pub fn new(player1: Player, player2: Player) -> Self {
-        let mut deck = Deck::new_full_deck();
+    pub fn new(players: Vec<Player>, config: GameConfig) -> Self {
+        let mut deck = Deck::new_for_variant(config.variant);
         deck.shuffle();
+        Game::new_with_deck(players, config, deck)
+    }
+
+    // a reproducible game: the deck is shuffled with the seed and every strategy is reseeded from it
+    pub fn new_seeded(mut players: Vec<Player>, config: GameConfig, seed: u64) -> Self {
+        let mut deck = Deck::new_for_variant(config.variant);
+        deck.shuffle_seeded(seed);
+        for (seat, player) in players.iter_mut().enumerate() {
+            player.strategy.seed_rng(seed.wrapping_add(1 + seat as u64));
+        }
+        Game::new_with_deck(players, config, deck)
+    }
 
-        let players = [player1, player2];
+    // starts a game with the given deck, cards are drawn from the end of the deck
+    pub fn new_with_deck(players: Vec<Player>, config: GameConfig, deck: Deck) -> Self {
+        assert!((2..=5).contains(&players.len()), "Hanabi is played with 2 to 5 players");
+        assert!(config.hand_size > 0 && config.max_mistakes > 0, "Hands and the mistakes allowed can't be empty");
+        let number_of_players = players.len();
+        let cards_per_hand = config.hand_size;
 
         let mut game = Game {
             players,
+            initial_deck: deck.clone(),
+            game_over_reported: false,
             deck,
-            fireworks: [0; 5],
-            hints_remaining: 8,
+            config,
+            fireworks: [0; 6],
+            hints_remaining: config.starting_hints,
             mistakes_made: 0,
             player_to_move: 0,
-            deck_empty_countdown: 2
+            deck_empty_countdown: number_of_players as u8,
+            discard_pile: Vec::new(),
+            hand_knowledge: vec![vec![DeckSubset::new_full_for(config.variant); cards_per_hand]; number_of_players],
+            observers: Vec::new(),
+            move_log: Vec::new(),
         };
 
-        // Deal initial hands
-        let mut player0_hand = Vec::new();
-        let mut player1_hand = Vec::new();
-        for _ in 0..5 {
-            player0_hand.push(game.players[0].draw(&mut game.deck));
-            player1_hand.push(game.players[1].draw(&mut game.deck));
+        // Deal initial hands, one card to each player in turn
+        for _ in 0..cards_per_hand {
+            for player in game.players.iter_mut() {
+                player.draw(&mut game.deck);
+            }
+        }
+
+        // every player learns who it hints to, so it can agree on conventions with them
+        let names: Vec<&'static str> = game.players.iter().map(|player| player.strategy.name()).collect();
+        for i in 0..number_of_players {
+            game.players[i].strategy.negotiate(names[(i + 1) % number_of_players]);
         }
 
-        // initialize players stretegy with other player's hand
-        game.players[0].strategy.initialize(&player1_hand);
-        game.players[1].strategy.initialize(&player0_hand);
+        // initialize players stretegy with the other player's hands, starting with the next player
+        for i in 0..number_of_players {
+            let other_hands: Vec<Vec<Card>> = (1..number_of_players)
+                .map(|offset| game.players[(i + offset) % number_of_players].hand.clone())
+                .collect();
+            game.players[i].strategy.initialize(&other_hands, &config);
+        }
 
         game
     }
 
+    // starts a game with exactly these hands and an empty deck, so no card is ever drawn and the
+    // hand indices stay where they are. with the deck empty from the start, the final round begins right away
+    #[cfg(any(test, feature = "scenarios"))]
+    pub fn new_with_hands(players: Vec<Player>, hands: Vec<Vec<Card>>) -> Self {
+        let config = GameConfig::for_players(players.len());
+        let cards_per_hand = config.hand_size;
+        assert!(hands.len() == players.len() && hands.iter().all(|hand| hand.len() == cards_per_hand),
+            "Every player needs a hand of {} cards", cards_per_hand);
+        let mut deck = Deck { cards: Vec::new() };
+        // dealing goes round the table starting with player 1, so the deck is stacked in reverse
+        for i in (0..cards_per_hand).rev() {
+            for hand in hands.iter().rev() {
+                deck.push_next(hand[i]);
+            }
+        }
+        Game::new_with_deck(players, config, deck)
+    }
+
+    // hints always go to the player after the one giving them
+    fn next_player(&self, player: usize) -> usize {
+        (player + 1) % self.players.len()
+    }
+
+    // tells every player except the one to move about the move,
+    // together with how many seats after them the moving player sits
+    fn notify_other_players(&mut self, mv: &Move, mv_result: &MoveResult) {
+        let number_of_players = self.players.len();
+        for offset in 1..number_of_players {
+            let observer_index = (self.player_to_move + number_of_players - offset) % number_of_players;
+            if let MoveResult::Play { drawn: Some(card), .. } | MoveResult::Discard { drawn: Some(card), .. } = mv_result {
+                self.players[observer_index].strategy.see(card);
+            }
+            self.players[observer_index].strategy.update_after_other_player_move(offset, mv, mv_result);
+        }
+    }
+
+    pub fn fireworks(&self) -> &[u8; 6] {
+        &self.fireworks
+    }
+
+    pub fn hints_remaining(&self) -> u8 {
+        self.hints_remaining
+    }
+
+    pub fn mistakes_made(&self) -> u8 {
+        self.mistakes_made
+    }
+
+    // discarded and misplayed cards, in the order they left the hands
+    pub fn discard_pile(&self) -> &[Card] {
+        &self.discard_pile
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    pub fn view(&self) -> GameView<'_> {
+        self.view_with_discards(&self.discard_pile)
+    }
+
+    // the view with the discard pile borrowed on its own, so the players can be borrowed mutably while it lives
+    fn view_with_discards<'a>(&self, discard_pile: &'a [Card]) -> GameView<'a> {
+        let number_of_players = self.players.len();
+        let mut hand_sizes = [0; 5];
+        for (offset, size) in hand_sizes.iter_mut().enumerate().take(number_of_players) {
+            *size = self.players[(self.player_to_move + offset) % number_of_players].hand.len();
+        }
+        GameView {
+            hints_remaining: self.hints_remaining,
+            mistakes_made: self.mistakes_made,
+            deck_remaining: self.deck.remaining(),
+            fireworks: self.fireworks,
+            discard_pile,
+            discard_allowed: self.config.discard_at_max_hints || self.hints_remaining < self.config.starting_hints,
+            hand_sizes,
+            number_of_players,
+        }
+    }
+
+    // the moves the player to move may make right now
+    pub fn legal_moves(&self) -> Vec<Move> {
+        legal_moves(&self.view(), &self.players[self.next_player(self.player_to_move)].hand)
+    }
+
     pub fn advance(&mut self) {
-        let player_index = self.player_to_move;
-        let selected_move = self.players[player_index].strategy.decide_move();
-        self.apply_move(selected_move);
+        let selected_move = self.decide_move();
+        if let Err(reason) = self.apply_move(selected_move) {
+            // a refused move leaves the game untouched, so the legal moves are still those of this turn
+            panic!("bot strategy made an illegal move {:?} ({:?}), the legal moves were {:?}", selected_move, reason, self.legal_moves());
+        }
     }
 
-    pub fn apply_move(&mut self, mv: Move) {
+    // asks the player to move for its move, after showing it the full state of the game.
+    // honest strategies ignore that, see Strategy::observe_full_state
+    pub fn decide_move(&mut self) -> Move {
+        let view = self.view_with_discards(&self.discard_pile);
+        let (before, rest) = self.players.split_at_mut(self.player_to_move);
+        let (player, after) = rest.split_first_mut().expect("the player to move is seated");
+        player.strategy.observe_full_state(&FullGameState {
+            my_hand: &player.hand,
+            other_hands: after.iter().chain(before.iter()).map(|p| p.hand.as_slice()).collect(),
+            deck: &self.deck.cards,
+            fireworks: self.fireworks,
+            hints_remaining: self.hints_remaining,
+        });
+        player.decide_move(&view)
+    }
+
+    // asks the strategy of the player to move without checking its answer, for a human whose
+    // move is checked with check_move and asked again
+    pub fn ask_strategy(&mut self) -> Move {
+        let view = self.view_with_discards(&self.discard_pile);
+        self.players[self.player_to_move].strategy.decide_move(&view)
+    }
+
+    // checks if the player to move is allowed to make this move
+    pub fn check_move(&self, mv: &Move) -> Result<(), MoveError> {
         match mv {
+            Move::Play(card_index) | Move::Discard(card_index) => {
+                if *card_index >= self.players[self.player_to_move].hand.len() {
+                    return Err(MoveError::IndexOutOfBounds);
+                }
+                if matches!(mv, Move::Discard(_)) && !self.config.discard_at_max_hints && self.hints_remaining >= self.config.starting_hints {
+                    return Err(MoveError::DiscardAtMaxHints);
+                }
+            }
+            Move::HintColor(color) => {
+                if self.hints_remaining == 0 {
+                    return Err(MoveError::NoHintsRemaining);
+                }
+                if *color == Color::Rainbow {
+                    return Err(MoveError::RainbowHint);
+                }
+                if !self.players[self.next_player(self.player_to_move)].hand.iter().any(|card| card.get_color().touched_by(*color)) {
+                    return Err(MoveError::HintMatchesNoCard);
+                }
+            }
+            Move::HintValue(value) => {
+                if self.hints_remaining == 0 {
+                    return Err(MoveError::NoHintsRemaining);
+                }
+                if !self.players[self.next_player(self.player_to_move)].hand.iter().any(|card| card.get_value() == *value) {
+                    return Err(MoveError::HintMatchesNoCard);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // applies a move of the player to move. an illegal move is refused and leaves the game untouched,
+    // so a front end can ask again. the result is the one the other players see, including the drawn card
+    pub fn apply_move(&mut self, mv: Move) -> Result<MoveResult, MoveError> {
+        self.check_move(&mv)?;
+        let player_index = self.player_to_move;
+        // the move that draws the last card does not count towards the final round
+        let deck_was_empty = self.deck.cards.is_empty();
+        let result = match mv {
             Move::Play(card_index) => self.play(card_index),
             Move::Discard(card_index) => self.discard(card_index),
             Move::HintColor(color) => self.give_hint_color(color),
             Move::HintValue(value) => self.give_hint_value(value),
+        };
+        if deck_was_empty {
+            self.deck_empty_countdown = self.deck_empty_countdown.saturating_sub(1);
+        }
+        self.player_to_move = self.next_player(self.player_to_move);
+        self.move_log.push((player_index, mv, result.clone()));
+
+        if !self.observers.is_empty() {
+            // taken out for the call, so the observers can get a view of the rest of the game
+            let mut observers = std::mem::take(&mut self.observers);
+            let view = self.view();
+            for observer in observers.iter_mut() {
+                observer.on_move(player_index, &mv, &view);
+            }
+            self.observers = observers;
+        }
+        Ok(result)
+    }
+
+    // every move made so far in order, with the index of the player who made it and its result
+    pub fn move_log(&self) -> &[(usize, Move, MoveResult)] {
+        &self.move_log
+    }
+
+    // the game in the json format hanab.live imports: the deck in draw order, the players and their actions.
+    // plays and discards name the card by its position in the deck, hints name the player receiving them
+    pub fn to_hanab_json(&self) -> String {
+        let number_of_players = self.players.len();
+        let deck: Vec<String> = self.initial_deck.cards.iter().rev()
+            .map(|card| format!("{{\"suitIndex\": {}, \"rank\": {}}}", card.get_color().index(), card.get_value()))
+            .collect();
+
+        // follow which deck positions each player holds, dealing the same way the game does
+        let mut next_card = 0;
+        let mut hands: Vec<Vec<usize>> = vec![Vec::new(); number_of_players];
+        for _ in 0..self.config.hand_size {
+            for hand in hands.iter_mut() {
+                if next_card < deck.len() {
+                    hand.push(next_card);
+                    next_card += 1;
+                }
+            }
+        }
+
+        let mut actions = Vec::new();
+        for (player, mv, _) in &self.move_log {
+            let receiver = self.next_player(*player);
+            actions.push(match mv {
+                Move::Play(idx) | Move::Discard(idx) => {
+                    let card = hands[*player].remove(*idx);
+                    if next_card < deck.len() {
+                        hands[*player].push(next_card);
+                        next_card += 1;
+                    }
+                    let action_type = if let Move::Play(_) = mv { 0 } else { 1 };
+                    format!("{{\"type\": {}, \"target\": {}}}", action_type, card)
+                }
+                Move::HintColor(color) => format!("{{\"type\": 2, \"target\": {}, \"value\": {}}}", receiver, color.index()),
+                Move::HintValue(value) => format!("{{\"type\": 3, \"target\": {}, \"value\": {}}}", receiver, value),
+            });
         }
-        self.player_to_move = if self.player_to_move == 0 { 1 } else { 0 };
+
+        let players: Vec<String> = (1..=number_of_players).map(|i| json_string(&format!("Player {}", i))).collect();
+        let variant = match self.config.variant {
+            GameVariant::Standard => "No Variant",
+            GameVariant::Rainbow => "Rainbow (6 Suits)",
+        };
+        format!("{{\"players\": [{}], \"deck\": [{}], \"actions\": [{}], \"options\": {{\"variant\": {}}}}}",
+            players.join(", "), deck.join(", "), actions.join(", "), json_string(variant))
     }
 
-    fn play(&mut self, card_index: usize) {
+    // plays a recorded game again on the deck it was dealt from, with the same number of players and config.
+    // panics if a move is made by a different player or turns out differently than recorded
+    #[cfg(any(test, feature = "scenarios"))]
+    pub fn replay(number_of_players: usize, config: GameConfig, deck: Deck, moves: &[(usize, Move, MoveResult)]) -> Self {
+        let players = (0..number_of_players).map(|_| Player::new(Box::new(ReplayStrategy))).collect();
+        let mut game = Game::new_with_deck(players, config, deck);
+        for (i, (player, mv, result)) in moves.iter().enumerate() {
+            assert_eq!(game.player_to_move, *player, "Move {} was made by another player", i);
+            let replayed = game.apply_move(*mv).unwrap_or_else(|reason| panic!("Move {} {:?} is illegal in the replay: {:?}", i, mv, reason));
+            assert_eq!(replayed, *result, "Move {} {:?} turned out differently", i, mv);
+        }
+        game
+    }
+
+    // applies the moves in order and stops at the first illegal one,
+    // returning its position in the list and why it was refused. the moves before it stay applied
+    #[cfg(any(test, feature = "scenarios"))]
+    pub fn apply_moves(&mut self, moves: &[Move]) -> Result<(), (usize, MoveError)> {
+        for (i, mv) in moves.iter().enumerate() {
+            self.apply_move(*mv).map_err(|reason| (i, reason))?;
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, card_index: usize) -> MoveResult {
         // Get the card to be played
         let card_played = self.players[self.player_to_move].hand[card_index]; 
-        let card_played_color_index = card_played.get_color() as usize;
+        let card_played_color_index = card_played.get_color().index();
         let card_played_value = card_played.get_value();
         self.players[self.player_to_move].hand.remove(card_index);
+        self.hand_knowledge[self.player_to_move].remove(card_index);
 
         // Draw a new card if possible
         let got_new_card: bool;
         let card_drawn: Option<Card>;
         if let Some(new_card) = self.deck.cards.pop() {
             self.players[self.player_to_move].hand.push(new_card);
+            self.hand_knowledge[self.player_to_move].push(DeckSubset::new_full_for(self.config.variant));
             card_drawn = Some(new_card);
             got_new_card = true;
         } else {
@@ -85,27 +471,32 @@ impl Game {
             self.fireworks[card_played_color_index] += 1;
             // Notify strategies of the successful play:
             // the player that payed the card doesnt see the new card drawn, the other player does
-            self.players[self.player_to_move].strategy.update_after_own_move(&Move::Play(card_index), &MoveResult::Play(true, card_played, None), got_new_card);
-            let other_player_index = if self.player_to_move == 0 { 1 } else { 0 };
-            self.players[other_player_index].strategy.update_after_other_player_move(&Move::Play(card_index), &MoveResult::Play(true, card_played, card_drawn));
+            self.players[self.player_to_move].strategy.update_after_own_move(&Move::Play(card_index), &MoveResult::Play { success: true, card: card_played, drawn: None }, got_new_card);
+            let result = MoveResult::Play { success: true, card: card_played, drawn: card_drawn };
+            self.notify_other_players(&Move::Play(card_index), &result);
             // increase hints if a firework is completed
-            if self.fireworks[card_played_color_index] == 5 && self.hints_remaining < 8 {
+            if self.fireworks[card_played_color_index] == 5 && self.hints_remaining < self.config.starting_hints {
                 self.hints_remaining += 1;
             }
+            result
         } else {
             // Failed play
             self.mistakes_made += 1;
-            self.players[self.player_to_move].strategy.update_after_own_move(&Move::Play(card_index), &MoveResult::Play(false, card_played, None), got_new_card);
-            let other_player_index = if self.player_to_move == 0 { 1 } else { 0 };
-            self.players[other_player_index].strategy.update_after_other_player_move(&Move::Play(card_index), &MoveResult::Play(false, card_played, card_drawn));
+            self.discard_pile.push(card_played);
+            self.players[self.player_to_move].strategy.update_after_own_move(&Move::Play(card_index), &MoveResult::Play { success: false, card: card_played, drawn: None }, got_new_card);
+            let result = MoveResult::Play { success: false, card: card_played, drawn: card_drawn };
+            self.notify_other_players(&Move::Play(card_index), &result);
+            result
         }
     }
 
-    fn discard(&mut self, card_index: usize) {
+    fn discard(&mut self, card_index: usize) -> MoveResult {
         // get the card to be discarded
         let card_discarded = self.players[self.player_to_move].hand.remove(card_index);
+        self.hand_knowledge[self.player_to_move].remove(card_index);
+        self.discard_pile.push(card_discarded);
         // increase hints
-        if self.hints_remaining < 8 {
+        if self.hints_remaining < self.config.starting_hints {
             self.hints_remaining += 1;
         }
          // Draw a new card if possible
@@ -113,6 +504,7 @@ impl Game {
         let card_drawn: Option<Card>;
         if let Some(new_card) = self.deck.cards.pop() {
             self.players[self.player_to_move].hand.push(new_card);
+            self.hand_knowledge[self.player_to_move].push(DeckSubset::new_full_for(self.config.variant));
             card_drawn = Some(new_card);
             got_new_card = true;
         } else {
@@ -120,41 +512,56 @@ impl Game {
             got_new_card = false;
         }
 
-        self.players[self.player_to_move].strategy.update_after_own_move(&Move::Discard(card_index), &MoveResult::Discard(card_discarded, None), got_new_card);
-        let other_player_index = if self.player_to_move == 0 { 1 } else { 0 };
-        self.players[other_player_index].strategy.update_after_other_player_move(&Move::Discard(card_index), &MoveResult::Discard(card_discarded, card_drawn));
+        self.players[self.player_to_move].strategy.update_after_own_move(&Move::Discard(card_index), &MoveResult::Discard { card: card_discarded, drawn: None }, got_new_card);
+        let result = MoveResult::Discard { card: card_discarded, drawn: card_drawn };
+        self.notify_other_players(&Move::Discard(card_index), &result);
+        result
     }
 
-    fn give_hint_color(&mut self, color: Color) {
-        if self.hints_remaining == 0 {
-            panic!("No hints remaining");
-        }
+    fn give_hint_color(&mut self, color: Color) -> MoveResult {
         self.hints_remaining -= 1;
-        let other_player_index = if self.player_to_move == 0 { 1 } else { 0 };
+        let other_player_index = self.next_player(self.player_to_move);
         let other_player = &self.players[other_player_index];
         let hinted_indices = other_player.hand.iter().enumerate()
-            .filter(|(_, card)| card.get_color() == color)
+            .filter(|(_, card)| card.get_color().touched_by(color))
             .map(|(index, _)| index)
             .collect::<Vec<usize>>();   // this collect takes more time than it should. optimize later
+        // rainbow cards are touched by every color, so a touched card is this color or rainbow
+        let touched = DeckSubset::from_color(color).union(&DeckSubset::from_color(Color::Rainbow));
+        for (i, knowledge) in self.hand_knowledge[other_player_index].iter_mut().enumerate() {
+            if hinted_indices.contains(&i) {
+                *knowledge = knowledge.intersect(&touched);
+            } else {
+                *knowledge = knowledge.intersect(&DeckSubset::from_color_inverted(color)).intersect(&DeckSubset::from_color_inverted(Color::Rainbow));
+            }
+        }
 
-        self.players[self.player_to_move].strategy.update_after_own_move(&Move::HintColor(color), &MoveResult::Hint(hinted_indices.clone()), false);
-        self.players[other_player_index].strategy.update_after_other_player_move(&Move::HintColor(color), &MoveResult::Hint(hinted_indices));
+        self.players[self.player_to_move].strategy.update_after_own_move(&Move::HintColor(color), &MoveResult::Hint { indices: hinted_indices.clone() }, false);
+        let result = MoveResult::Hint { indices: hinted_indices };
+        self.notify_other_players(&Move::HintColor(color), &result);
+        result
     }
 
-    fn give_hint_value(&mut self, value: u8) {
-        if self.hints_remaining == 0 {
-            panic!("No hints remaining");
-        }
+    fn give_hint_value(&mut self, value: u8) -> MoveResult {
         self.hints_remaining -= 1;
-        let other_player_index = if self.player_to_move == 0 { 1 } else { 0 };
+        let other_player_index = self.next_player(self.player_to_move);
         let other_player = &self.players[other_player_index];
         let hinted_indices = other_player.hand.iter().enumerate()
             .filter(|(_, card)| card.get_value() == value)
             .map(|(index, _)| index)
             .collect::<Vec<usize>>();   // this collect takes more time than it should. optimize later
+        for (i, knowledge) in self.hand_knowledge[other_player_index].iter_mut().enumerate() {
+            if hinted_indices.contains(&i) {
+                *knowledge = knowledge.intersect(&DeckSubset::from_value(value));
+            } else {
+                *knowledge = knowledge.intersect(&DeckSubset::from_value_inverted(value));
+            }
+        }
 
-        self.players[self.player_to_move].strategy.update_after_own_move(&Move::HintValue(value), &MoveResult::Hint(hinted_indices.clone()), false);
-        self.players[other_player_index].strategy.update_after_other_player_move(&Move::HintValue(value), &MoveResult::Hint(hinted_indices));
+        self.players[self.player_to_move].strategy.update_after_own_move(&Move::HintValue(value), &MoveResult::Hint { indices: hinted_indices.clone() }, false);
+        let result = MoveResult::Hint { indices: hinted_indices };
+        self.notify_other_players(&Move::HintValue(value), &result);
+        result
     }
 
     // pub fn display_game_state(&self) {
@@ -167,20 +574,211 @@ impl Game {
     //     }
     // }
 
-    pub fn game_over(&mut self) -> Option<u8> {
-        if self.mistakes_made >= 3 {
-            return Some(0);
+    // true once the game has ended: three mistakes, all fireworks complete or the final round played
+    pub fn is_terminal(&self) -> bool {
+        self.mistakes_made >= self.config.max_mistakes || self.deck_empty_countdown == 0 || self.fireworks[..self.config.variant.colors().len()].iter().all(|&f| f == 5)
+    }
+
+    // the points the game is worth right now, a lost game is worth nothing
+    pub fn score(&self) -> u8 {
+        if self.mistakes_made >= self.config.max_mistakes {
+            return 0;
         }
-        if self.fireworks.iter().all(|&f| f == 5) || self.deck_empty_countdown == 0 {
-            let score: u8 = self.fireworks.iter().sum();
-            Some(score)
-        } else {
-            if self.deck.cards.is_empty() {
-                if self.deck_empty_countdown > 0 {
-                    self.deck_empty_countdown -= 1;
+        self.fireworks.iter().sum()
+    }
+
+    // returns the score if the game has ended. after the last card is drawn every player gets
+    // exactly one more move. the first time it sees the end, every strategy is told the final score
+    pub fn game_over(&mut self) -> Option<u8> {
+        if self.is_terminal() {
+            let final_score = self.score();
+            if !self.game_over_reported {
+                self.game_over_reported = true;
+                for player in self.players.iter_mut() {
+                    player.strategy.on_game_over(final_score);
                 }
             }
-            None
+            return Some(final_score);
+        }
+        None
+    }
+}
+
+// takes the seats while a recorded game is replayed, the moves come from the log
+#[cfg(any(test, feature = "scenarios"))]
+struct ReplayStrategy;
+
+#[cfg(any(test, feature = "scenarios"))]
+impl crate::strategy::Strategy for ReplayStrategy {
+    fn name(&self) -> &'static str { "Replay" }
+
+    fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+
+    fn reset(&mut self) {}
+
+    fn decide_move(&mut self, _view: &GameView) -> Move {
+        unreachable!("a replayed game only applies recorded moves")
+    }
+
+    fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+
+    fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::robert::Robert;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand::seq::IndexedRandom;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // writes every move it is told about into a log the test keeps a handle to
+    struct Recorder(Rc<RefCell<Vec<(usize, Move)>>>);
+
+    impl GameObserver for Recorder {
+        fn on_move(&mut self, player: usize, mv: &Move, _view: &GameView) {
+            self.0.borrow_mut().push((player, *mv));
+        }
+    }
+
+    // a two player game with these hands and three white 1s left in the deck, the moves are applied by hand
+    fn game_with_hands(hands: Vec<Vec<Card>>) -> Game {
+        let players = (0..hands.len()).map(|_| Player::new(Box::new(ReplayStrategy))).collect();
+        let mut game = Game::new_with_hands(players, hands);
+        game.deck.cards = vec![Card::new(40), Card::new(41), Card::new(42)];
+        game
+    }
+
+    fn red_and_green_hands() -> Vec<Vec<Card>> {
+        vec![(0..5).map(Card::new).collect(), (10..15).map(Card::new).collect()]
+    }
+
+    #[test]
+    fn apply_moves_stops_at_the_first_illegal_move() {
+        let mut game = game_with_hands(red_and_green_hands());
+        let moves = [Move::HintColor(Color::Green), Move::HintColor(Color::Red), Move::Play(0), Move::Play(7), Move::Play(0)];
+        assert_eq!(game.apply_moves(&moves), Err((3, MoveError::IndexOutOfBounds)));
+        assert_eq!(game.move_log().len(), 3);
+        assert_eq!(game.fireworks()[Color::Red.index()], 1);
+    }
+
+    #[test]
+    fn every_observer_sees_every_move_once() {
+        let mut game = game_with_hands(red_and_green_hands());
+        let logs = [Rc::new(RefCell::new(Vec::new())), Rc::new(RefCell::new(Vec::new()))];
+        for log in &logs {
+            game.add_observer(Box::new(Recorder(Rc::clone(log))));
+        }
+        let moves = [Move::HintColor(Color::Green), Move::HintColor(Color::Red), Move::Play(0)];
+        game.apply_moves(&moves).unwrap();
+
+        let expected: Vec<(usize, Move)> = vec![(0, moves[0]), (1, moves[1]), (0, moves[2])];
+        for log in &logs {
+            assert_eq!(*log.borrow(), expected);
+        }
+    }
+
+    // random legal moves for 2 to 5 players, the game is over exactly when it is terminal
+    #[test]
+    fn game_over_agrees_with_is_terminal() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for seed in 0..40 {
+            let number_of_players = 2 + seed as usize % 4;
+            let players = (0..number_of_players).map(|_| Player::new(Box::new(ReplayStrategy))).collect();
+            let mut game = Game::new_seeded(players, GameConfig::for_players(number_of_players), seed);
+            loop {
+                let terminal = game.is_terminal();
+                assert_eq!(game.game_over().is_some(), terminal);
+                if terminal { break; }
+                let mv = *game.legal_moves().choose(&mut rng).expect("a running game has a legal move");
+                game.apply_move(mv).unwrap();
+            }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn every_illegal_move_is_refused_with_its_reason() {
+        let mut game = game_with_hands(red_and_green_hands());
+        assert_eq!(game.apply_move(Move::Play(5)), Err(MoveError::IndexOutOfBounds));
+        assert_eq!(game.apply_move(Move::Discard(9)), Err(MoveError::IndexOutOfBounds));
+        assert_eq!(game.apply_move(Move::HintColor(Color::Rainbow)), Err(MoveError::RainbowHint));
+        assert_eq!(game.apply_move(Move::HintColor(Color::Blue)), Err(MoveError::HintMatchesNoCard));
+        assert_eq!(game.apply_move(Move::HintValue(4)), Err(MoveError::HintMatchesNoCard));
+        assert!(game.move_log().is_empty());
+
+        // the players spend all eight hint tokens on each other
+        for _ in 0..4 {
+            game.apply_moves(&[Move::HintColor(Color::Green), Move::HintColor(Color::Red)]).unwrap();
+        }
+        assert_eq!(game.apply_move(Move::HintValue(1)), Err(MoveError::NoHintsRemaining));
+        assert_eq!(game.apply_move(Move::HintColor(Color::Green)), Err(MoveError::NoHintsRemaining));
+
+        let strict = GameConfig { discard_at_max_hints: false, ..GameConfig::for_players(2) };
+        let players = (0..2).map(|_| Player::new(Box::new(ReplayStrategy))).collect();
+        let mut game = Game::new_with_deck(players, strict, Deck::new_full_deck());
+        assert_eq!(game.apply_move(Move::Discard(0)), Err(MoveError::DiscardAtMaxHints));
+        game.apply_move(Move::HintValue(4)).unwrap();
+        assert!(game.apply_move(Move::Discard(0)).is_ok());
+    }
+
+    // one card is left after the deal. the move that draws it is not part of the final round,
+    // after it every player moves exactly once more
+    #[test]
+    fn every_player_moves_once_after_the_last_draw() {
+        for number_of_players in [2, 3] {
+            let config = GameConfig::for_players(number_of_players);
+            let mut deck = Deck::new_full_deck();
+            deck.cards.drain(..50 - (number_of_players * config.hand_size + 1));
+            let players = (0..number_of_players).map(|_| Player::new(Box::new(ReplayStrategy))).collect();
+            let mut game = Game::new_with_deck(players, config, deck);
+            assert_eq!(game.deck.remaining(), 1);
+
+            game.apply_move(Move::Discard(0)).unwrap();
+            assert_eq!(game.deck.remaining(), 0);
+            for _ in 0..number_of_players {
+                assert!(!game.is_terminal(), "{} players: the game ended before everyone moved", number_of_players);
+                game.apply_move(Move::Discard(0)).unwrap();
+            }
+            assert!(game.is_terminal(), "{} players: the game went on after the final round", number_of_players);
+            assert_eq!(game.move_log().len(), number_of_players + 1);
+        }
+    }
+
+    // a hint that would touch no card is refused before it costs a token, and nobody is told about it
+    #[test]
+    fn hint_touching_no_card_costs_no_token() {
+        let mut game = game_with_hands(red_and_green_hands());
+        game.apply_move(Move::HintColor(Color::Green)).unwrap();
+        assert_eq!(game.hints_remaining, 7);
+
+        // player 2 sees only red cards in player 1's hand
+        assert_eq!(game.apply_move(Move::HintColor(Color::White)), Err(MoveError::HintMatchesNoCard));
+        assert_eq!(game.apply_move(Move::HintValue(5)), Err(MoveError::HintMatchesNoCard));
+        assert_eq!(game.hints_remaining, 7);
+        assert_eq!(game.player_to_move, 1);
+        assert_eq!(game.move_log().len(), 1);
+    }
+
+    // how a situation is staged: fixed hands, a few hints, then the strategy is asked. the red 1 in
+    // slot 2 is hinted as red and as a 1 on an empty board, so Robert plays it
+    #[test]
+    fn staged_hand_plays_the_hinted_red_one() {
+        let players = vec![Player::new(Box::new(Robert::new())), Player::new(Box::new(Robert::new()))];
+        let my_hand = vec![Card::new(3), Card::new(15), Card::new(0), Card::new(27), Card::new(39)];
+        let mut game = Game::new_with_hands(players, vec![my_hand, (10..15).map(Card::new).collect()]);
+        game.deck.cards = vec![Card::new(40), Card::new(41), Card::new(42)];
+        game.apply_moves(&[Move::HintColor(Color::Green), Move::HintColor(Color::Red), Move::HintValue(1), Move::HintValue(1)]).unwrap();
+        assert_eq!(game.decide_move(), Move::Play(2));
+    }
+
+    // names go into the hanab.live json as string literals, whatever characters they hold
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("Player 1"), "\"Player 1\"");
+        assert_eq!(json_string("say \"hi\"\\\n"), "\"say \\\"hi\\\"\\\\\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
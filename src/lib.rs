@@ -0,0 +1,22 @@
+// the game engine and the strategies, shared by the hanabi binary and anything that wants to
+// evaluate strategies without going through the command line
+pub mod enums;
+pub mod card;
+pub mod deck;
+pub mod player;
+pub mod game;
+pub mod decksubset;
+pub mod strategy;
+pub mod strategy_utils;
+pub mod conventions;
+pub mod strategies;
+pub mod metrics;
+pub mod observer;
+pub mod config;
+pub mod evaluate;
+
+use crate::strategy::Strategy;
+
+// builds a fresh strategy for every game, Sync so the games can run in parallel
+pub type StrategyFn = dyn Fn() -> Box<dyn Strategy> + Sync;
+pub type StrategyFactory<'a> = &'a StrategyFn;
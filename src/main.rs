@@ -1,72 +1,259 @@
 use rayon::prelude::*;
-use std::rc::Rc;
-use std::cell::RefCell;
-use crate::strategies::cheater::{Cheater, CheatSharedState};
-
-mod enums;
-mod card;
-mod deck;
-mod player;
-mod game;
-mod decksubset;
-mod strategy;
-mod strategies;
+use hanabi::strategies;
+use hanabi::strategies::cheater::Cheater;
+use hanabi::strategies::robert::{Robert, Params};
+use hanabi::config;
+
 mod evolve_robert;
 
 use std::env;
-use crate::game::Game;
-use crate::player::Player;
-use crate::strategy::Strategy;
-use crate::enums::Move;
-
-// Number of games to run in benchmark mode
-const GAMES_TO_SIMULATE: u32 = 10000;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use hanabi::game::{Game, GameConfig};
+use hanabi::card::CardGroups;
+use hanabi::deck::{Deck, DeckComposition, BURIED_DRAWS};
+use hanabi::player::Player;
+use hanabi::enums::{Move, Color, GameVariant};
+use hanabi::observer::StatusPrinter;
+use hanabi::metrics::{Metric, EntropyMetric, CalibrationMetric};
+use hanabi::{StrategyFn, StrategyFactory};
+use hanabi::evaluate::{evaluate, play_game, seat_strategies, GameStats};
 
-type StrategyFactory = fn() -> Box<dyn Strategy>;
+// Number of games to run in benchmark mode unless --games is given
+const DEFAULT_GAMES_TO_SIMULATE: u32 = 10000;
 
 fn main() {
 
-    // Registry of strategies.
-    let all_strategies: Vec<(&str, StrategyFactory)> = vec![
-        ("Gemini", || Box::new(strategies::gemini::Gemini::new())),
-        ("ChatGPT", || Box::new(strategies::chatgpt::ChatGPT::new())),
-        ("Robert", || Box::new(strategies::robert::Robert::new())),
-        ("Human", || Box::new(strategies::human::Human::new())),
-    ];
+    let all_strategies = strategies::registry();
+    let interactive = strategies::INTERACTIVE;
 
     // --- Argument Parsing ---
     let args: Vec<String> = env::args().collect();
 
     // Check for evolution mode
     if args.contains(&"evolve-robert".to_string()) {
-        evolve_robert::run_evolution();
+        // --vs <strategies...> evolves against these opponents instead of in self-play
+        let mut opponents: Vec<(&str, StrategyFactory)> = Vec::new();
+        if let Some(i) = args.iter().position(|arg| arg == "--vs") {
+            for name in args[i + 1..].iter().take_while(|arg| !arg.starts_with("--")) {
+                match all_strategies.iter().find(|(n, _)| n == name) {
+                    Some(&pair) if !interactive.contains(&pair.0) => opponents.push(pair),
+                    _ => {
+                        println!("Unknown opponent '{}' for --vs", name);
+                        return;
+                    }
+                }
+            }
+            if opponents.is_empty() {
+                println!("--vs needs at least one strategy name");
+                return;
+            }
+        }
+        // --resume continues from the last checkpoint instead of starting over
+        evolve_robert::run_evolution(args.contains(&"--resume".to_string()), &opponents);
+        return;
+    }
+
+    // --games <n> sets how many games every benchmark mode simulates
+    let games = match args.iter().position(|arg| arg == "--games") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse::<u32>().ok()) {
+            Some(n) if n >= 1 => n,
+            _ => {
+                println!("--games needs a number of games of at least 1");
+                return;
+            }
+        },
+        None => DEFAULT_GAMES_TO_SIMULATE,
+    };
+
+    // --threads <n> runs the benchmarks on n threads instead of on every core
+    let threads = match args.iter().position(|arg| arg == "--threads") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if n >= 1 => Some(n),
+            _ => {
+                println!("--threads needs a number of threads of at least 1");
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // --dump-robert-params [path] writes Robert's default params, robert_params.txt if no path is given
+    if let Some(i) = args.iter().position(|arg| arg == "--dump-robert-params") {
+        let path = args.get(i + 1).filter(|arg| !arg.starts_with("--")).map(|arg| arg.as_str()).unwrap_or("robert_params.txt");
+        if std::path::Path::new(path).exists() {
+            println!("{} already exists, not overwriting it", path);
+        } else if let Err(e) = Params::write_default_template(path) {
+            println!("Could not write {}: {}", path, e);
+        } else {
+            println!("Wrote the default Robert params to {}", path);
+        }
+        return;
+    }
+
+    // Check for deal inspection mode
+    if args.contains(&"--deal".to_string()) {
+        run_deal();
         return;
     }
 
     // Check for Cheater simulation mode
     if args.contains(&"--cheater".to_string()) {
-        run_cheater_benchmark();
+        run_cheater_benchmark(games);
         return;
     }
 
-    // Find selected strategies based on args
-    let mut selected_strategies: Vec<(&str, StrategyFactory)> = Vec::new();
+    // --robert-params <path> registers RobertFile, a Robert playing with the params from that file
+    let robert_params_path = args.iter().position(|arg| arg == "--robert-params").map(|i| args.get(i + 1));
+    let robert_file: Option<Box<StrategyFn>> = match robert_params_path {
+        Some(Some(path)) if std::path::Path::new(path).is_file() => {
+            // read the file once here, not for every game
+            let params = Params::load_from_file_or_default(path);
+            Some(Box::new(move || Box::new(Robert::new_with_params(params))))
+        }
+        Some(Some(path)) => {
+            println!("Could not find the params file {}", path);
+            return;
+        }
+        Some(None) => {
+            println!("--robert-params needs the path of a params file");
+            return;
+        }
+        None => None,
+    };
 
-    // We look for strategy names in the arguments preserving order (optional, but good for P1 vs P2)
-    // If we iterate through args, we can pick them up.
-    // Alternatively, just iterate the registry and check containment to allow unordered args.
-    // The prompt implies "two strategy names in any order".
-    // Let's filter the args to find valid strategy names.
+    // Round robin over every registered strategy except the interactive ones
+    if args.contains(&"--tournament".to_string()) {
+        let mut contestants: Vec<(&str, StrategyFactory)> = all_strategies.iter().filter(|(name, _)| !interactive.contains(name)).copied().collect();
+        if let Some(factory) = &robert_file {
+            contestants.push(("RobertFile", factory.as_ref()));
+        }
+        with_threads(threads, || run_tournament(&contestants, games));
+        return;
+    }
 
+    // Robert[<name>] selects the named param set [<name>] from robert_params.txt
+    let mut named_roberts: Vec<(&str, Box<StrategyFn>)> = Vec::new();
     for arg in &args {
-        if let Some(pair) = all_strategies.iter().find(|(name, _)| *name == arg) {
-            selected_strategies.push(*pair);
+        if let Some(section) = arg.strip_prefix("Robert[").and_then(|s| s.strip_suffix(']')) {
+            match Params::load_named(&config::resolve_config_path("robert_params.txt"), section) {
+                Ok(params) => named_roberts.push((arg, Box::new(move || Box::new(Robert::new_with_params(params))))),
+                Err(e) => {
+                    println!("{}", e);
+                    return;
+                }
+            }
         }
     }
 
+    // every name that can be seated: the registry, the named Robert param sets and RobertFile
+    let mut known_strategies: Vec<(&str, StrategyFactory)> = all_strategies.clone();
+    known_strategies.extend(named_roberts.iter().map(|(name, factory)| (*name, factory.as_ref())));
+    if let Some(factory) = &robert_file {
+        known_strategies.push(("RobertFile", factory.as_ref()));
+    }
+    let find_strategy = |arg: &str| known_strategies.iter().find(|(name, _)| *name == arg).copied();
+
+    // strategy names are seated in the order they are given, P1 first. The same name may be given twice
+    let seat_flags = ["--p1", "--p2"];
+    let mut selected_strategies: Vec<(&str, StrategyFactory)> = args.iter().enumerate().skip(1)
+        .filter(|(i, _)| !seat_flags.contains(&args[i - 1].as_str()))
+        .filter_map(|(_, arg)| find_strategy(arg))
+        .collect();
+
+    // --p1 <name> and --p2 <name> seat a strategy explicitly, the other names fill the remaining seat
+    for (seat, flag) in seat_flags.iter().enumerate() {
+        if let Some(i) = args.iter().position(|arg| arg == flag) {
+            match args.get(i + 1).and_then(|name| find_strategy(name)) {
+                Some(pair) => selected_strategies.insert(seat.min(selected_strategies.len()), pair),
+                None => {
+                    println!("{} needs the name of a strategy", flag);
+                    return;
+                }
+            }
+        }
+    }
+
+    // --players <n> seats n players in the benchmark, taking turns between the two strategies
+    let number_of_players = match args.iter().position(|arg| arg == "--players") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) if (2..=5).contains(&n) => n,
+            _ => {
+                println!("--players needs a number of players from 2 to 5");
+                return;
+            }
+        },
+        None => 2,
+    };
+
+    // --seed <n> replays the same deals in the benchmark, and a single game dealt from that seed with --single or --step
+    let seed = match args.iter().position(|arg| arg == "--seed") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse::<u64>().ok()) {
+            Some(n) => Some(n),
+            None => {
+                println!("--seed needs a number");
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // --dump-worst <n> lists the seeds of the n lowest scoring benchmark games, to replay them with --single --seed
+    let dump_worst = match args.iter().position(|arg| arg == "--dump-worst") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) => Some(n),
+            None => {
+                println!("--dump-worst needs a number of games");
+                return;
+            }
+        },
+        None => None,
+    };
+
+    // --format csv|json prints the benchmark for other programs instead of as text
+    let format = match args.iter().position(|arg| arg == "--format") {
+        Some(i) => match args.get(i + 1).map(|f| f.as_str()) {
+            Some("text") => OutputFormat::Text,
+            Some("csv") => OutputFormat::Csv,
+            Some("json") => OutputFormat::Json,
+            _ => {
+                println!("--format needs one of text, csv or json");
+                return;
+            }
+        },
+        None => OutputFormat::Text,
+    };
+
+    // --rainbow plays the benchmark with the sixth, rainbow suit
+    let variant = if args.contains(&"--rainbow".to_string()) { GameVariant::Rainbow } else { GameVariant::Standard };
+
+    // point out misspelled strategy names, everything that is not a flag or a known name counts as one
+    for (i, arg) in args.iter().enumerate().skip(1) {
+        if arg.starts_with("--") || args[i - 1] == "--robert-params" || arg == "evolve-robert" || arg.parse::<u64>().is_ok() || ["text", "csv", "json"].contains(&arg.as_str()) || selected_strategies.iter().any(|(name, _)| name == arg) {
+            continue;
+        }
+        let names: Vec<&str> = all_strategies.iter().map(|(name, _)| *name).collect();
+        if let Some(name) = closest_name(arg, &names) {
+            eprintln!("Unknown strategy '{}', did you mean '{}'?", arg, name);
+        }
+    }
+
+    // without a params file Robert silently plays with its defaults, point out how to get a file to tune.
+    // on stderr, so csv and json output stay clean
+    let robert_seated = selected_strategies.iter().any(|(name, _)| name.starts_with("Robert"));
+    if robert_seated && !std::path::Path::new(&config::resolve_config_path("robert_params.txt")).is_file() {
+        eprintln!("note: robert_params.txt not found, Robert plays with its default params (--dump-robert-params writes them to a file)");
+    }
+
+    // a single valid strategy plays against itself. on stderr, so csv and json output stay clean
+    if seat_for_self_play(&mut selected_strategies) {
+        eprintln!("Only one strategy specified, running {} in self-play.", selected_strategies[0].0);
+    }
+
     // Default fallback if not enough args provided
     if selected_strategies.len() < 2 {
-        println!("Not enough strategies specified. Usage: cargo run -- <Strat1> <Strat2> [--single]");
+        println!("Not enough strategies specified. Usage: cargo run -- <Strat1> [<Strat2>] | --p1 <Strat> --p2 <Strat> [--single [--entropy] [--debug] [--hanab] | --step | --tournament | [--players <n>] [--seed <n>] [--dump-worst <n>] [--games <n>] [--threads <n>] [--rainbow] [--timing] [--profile] [--format text|csv|json] [--robert-params <path> (adds RobertFile)] | --dump-robert-params [<path>] | --fixed-deck | --calibration | --compositions | --seats]");
         println!("Available strategies: {:?}", all_strategies.iter().map(|(n, _)| n).collect::<Vec<_>>());
         // For safety, just exit or default to something safe if you prefer
         return;
@@ -76,7 +263,9 @@ fn main() {
     let (p1_name, p1_factory) = selected_strategies[0];
     let (p2_name, p2_factory) = selected_strategies[1];
 
-    let mut single_mode = args.contains(&"--single".to_string());
+    // --step watches a single game move by move
+    let step_mode = args.contains(&"--step".to_string());
+    let mut single_mode = step_mode || args.contains(&"--single".to_string());
 
     // Force single mode if Human is involved
     if p1_name == "Human" || p2_name == "Human" && !single_mode {
@@ -85,64 +274,508 @@ fn main() {
     }
 
     // --- Execution ---
-    println!("Matchup: P1 [{}] vs P2 [{}]", p1_name, p2_name);
+    let fingerprint = environment_fingerprint(&all_strategies.iter().map(|(n, _)| *n).collect::<Vec<_>>());
+    // json carries the fingerprint inside the object, the others start with it
+    if format != OutputFormat::Json {
+        // starts with # so the line is skipped by csv readers if the output is saved
+        println!("# {}", fingerprint);
+    }
+    if format == OutputFormat::Text {
+        println!("Matchup: P1 [{}] vs P2 [{}]", p1_name, p2_name);
+    }
 
     if single_mode {
-        run_single_game(p1_name, p1_factory, p2_name, p2_factory);
+        let mut metrics: Vec<Box<dyn Metric>> = Vec::new();
+        if args.contains(&"--entropy".to_string()) {
+            metrics.push(Box::new(EntropyMetric::new()));
+        }
+        let debug = args.contains(&"--debug".to_string());
+        // --hanab prints the finished game for import on hanab.live
+        let hanab_json = args.contains(&"--hanab".to_string());
+        if step_mode {
+            run_step_game(p1_factory, p2_factory, seed);
+        } else {
+            run_single_game(p1_factory, p2_factory, &mut metrics, debug, hanab_json, seed);
+        }
     } else {
-        run_benchmark(p1_factory, p2_factory);
+        with_threads(threads, || {
+            if args.contains(&"--fixed-deck".to_string()) {
+                run_fixed_deck_benchmark(p1_factory, p2_factory, games);
+            } else if args.contains(&"--calibration".to_string()) {
+                run_calibration_benchmark(p1_factory, p2_factory, games);
+            } else if args.contains(&"--compositions".to_string()) {
+                run_composition_benchmark(p1_factory, p2_factory, games);
+            } else if args.contains(&"--seats".to_string()) {
+                run_seat_benchmark(p1_factory, p2_factory, games);
+            } else {
+                run_benchmark(p1_factory, p2_factory, games, number_of_players, variant, seed, dump_worst, args.contains(&"--timing".to_string()), args.contains(&"--profile".to_string()), format, &fingerprint);
+            }
+        });
     }
 }
 
-fn run_single_game_bench(strat1: StrategyFactory, strat2: StrategyFactory) -> u8 {
-    let p1 = Player::new(strat1());
-    let p2 = Player::new(strat2());
-    let mut game = Game::new(p1, p2);
+/// Runs f on a rayon pool of this many threads, or on the global pool using every core if None
+fn with_threads<R: Send>(threads: Option<usize>, f: impl FnOnce() -> R + Send) -> R {
+    match threads {
+        Some(n) => rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()
+            .expect("could not start the benchmark threads")
+            .install(f),
+        None => f(),
+    }
+}
 
-    // Run game loop until game_over returns a score
-    loop {
-        if let Some(final_score) = game.game_over() {
-            return final_score;
+/// Levenshtein distance between two strings, used to suggest strategy names for typos
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // previous row of the distance table, starting with the distances from the empty prefix of a
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
         }
-        game.advance();
-    };
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The registered name closest to a misspelled one
+fn closest_name<'a>(arg: &str, names: &[&'a str]) -> Option<&'a str> {
+    names.iter().copied().min_by_key(|name| edit_distance(arg, name))
 }
 
-/// Runs GAMES_TO_SIMULATE games and prints statistics
-fn run_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory) {
-    println!("Simulating {} games...", GAMES_TO_SIMULATE);
+/// Seats a single strategy a second time so it plays against itself, true if it did
+fn seat_for_self_play<T: Copy>(selected: &mut Vec<T>) -> bool {
+    if selected.len() != 1 { return false; }
+    selected.push(selected[0]);
+    true
+}
+
+/// Describes the build and the params the results were produced with, so archived results can be reproduced
+fn environment_fingerprint(strategy_names: &[&str]) -> String {
+    let params = Params::load_from_file_or_default(&config::resolve_config_path("robert_params.txt"));
+    format!("hanabi {} | strategies: {} | robert params: {:016x}",
+        env!("CARGO_PKG_VERSION"), strategy_names.join(", "), params.fingerprint())
+}
+
+// how the benchmark prints its results
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Csv,  // one game_index,score,turns,cards_left line per game
+    Json, // one summary object
+}
+
+/// Runs the given number of games and prints statistics
+/// With timing, a warm-up batch is played first and the time per game is reported as well
+/// With profile, the time each strategy spent deciding its moves is reported
+/// Game i is dealt from seed ^ i, so the same games are replayed on every run. Without a seed
+/// a random one is drawn, so every game can still be replayed with --single --seed
+/// With dump_worst, the seeds of that many lowest scoring games are listed
+/// Csv and json print the scores, turns and cards left and their summary, without the timing, and the worst games on stderr
+#[allow(clippy::too_many_arguments)]
+fn run_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory, games: u32, number_of_players: usize, variant: GameVariant, seed: Option<u64>, dump_worst: Option<usize>, timing: bool, profile: bool, format: OutputFormat, fingerprint: &str) {
+    let base_seed = seed.unwrap_or_else(rand::random);
+    // the flags a replay with --single needs besides the seed, it plays two players without rainbow
+    let replayable = number_of_players == 2 && variant == GameVariant::Standard;
+    if format != OutputFormat::Text {
+        let progress = Progress::new(games);
+        let results: Vec<GameStats> = (0..games)
+                        .into_par_iter()
+                        .map_init(|| seat_strategies(p1_factory, p2_factory, number_of_players), |strategies, i| {
+                            let stats = play_game(strategies, variant, Some(base_seed ^ i as u64));
+                            progress.tick();
+                            stats
+                        })
+                        .collect();
+        progress.finish();
+        print_scores(&results, variant.max_score(), format, fingerprint);
+        if let Some(n) = dump_worst {
+            let scored_seeds: Vec<(u8, u64)> = results.iter().filter_map(|r| Some((r.score, r.seed?))).collect();
+            for line in worst_games(&scored_seeds, n, replayable) {
+                eprintln!("{}", line);
+            }
+        }
+        return;
+    }
+
+    if timing {
+        // warm up caches and the thread pool so the first measured games are not slower than the rest
+        let warm_up_games = (games / 100).max(1);
+        println!("Warming up with {} games...", warm_up_games);
+        (0..warm_up_games).into_par_iter().for_each_init(|| seat_strategies(p1_factory, p2_factory, number_of_players), |strategies, _| { play_game(strategies, variant, None); });
+    }
 
-    let scores: Vec<u8> = (0..GAMES_TO_SIMULATE)
+    println!("Simulating {} games...", games);
+    let start = Instant::now();
+
+    let progress = Progress::new(games);
+    let results: Vec<GameStats> = (0..games)
                     .into_par_iter()
-                    .map(|_| run_single_game_bench(p1_factory, p2_factory))
+                    .map_init(|| seat_strategies(p1_factory, p2_factory, number_of_players), |strategies, i| {
+                        // the strategies are built once per rayon job and reset between its games
+                        let stats = play_game(strategies, variant, Some(base_seed ^ i as u64));
+                        progress.tick();
+                        stats
+                    })
                     .collect();
+    let total_time = start.elapsed();
+    progress.finish();
 
     let mut total_score: u32 = 0;
+    let mut total_turns: u32 = 0;
+    let mut total_cards_left: usize = 0;
     let mut perfect_games = 0;
     let mut zero_score_games = 0;
 
-    for score in scores.iter() {
-        total_score += *score as u32;
-        if *score == 25 {
+    for result in results.iter() {
+        total_score += result.score as u32;
+        total_turns += result.turns;
+        total_cards_left += result.cards_left;
+        if result.score == variant.max_score() {
             perfect_games += 1;
         }
-        if *score == 0 {
+        if result.score == 0 {
             zero_score_games += 1;
         }
     }
-    let average_score = total_score as f64 / GAMES_TO_SIMULATE as f64;
+    let average_score = total_score as f64 / games as f64;
+    let scores: Vec<u8> = results.iter().map(|r| r.score).collect();
+    let summary = ScoreSummary::new(&scores, variant.max_score());
     println!("  -> Average Score:     {:.4}", average_score);
-    println!("  -> Perfect Games (25): {}", perfect_games);
+    println!("  -> Std Deviation:     {:.4}", summary.stddev);
+    println!("  -> Median (5%/95%):   {} ({}/{})", summary.median, summary.percentile_5, summary.percentile_95);
+    println!("  -> Perfect Games ({}): {}", variant.max_score(), perfect_games);
     println!("  -> Lost Games (0):     {}", zero_score_games);
+    println!("  -> Average Turns:     {:.4}", total_turns as f64 / games as f64);
+    println!("  -> Avg Cards Left:    {:.4}", total_cards_left as f64 / games as f64);
+    println!("  -> Score Histogram:");
+    summary.print_histogram();
+
+    if let Some(n) = dump_worst {
+        let scored_seeds: Vec<(u8, u64)> = results.iter().filter_map(|r| Some((r.score, r.seed?))).collect();
+        for line in worst_games(&scored_seeds, n, replayable) {
+            println!("{}", line);
+        }
+    }
+
+    if timing {
+        // the slowest 1% of games are dropped from the per-game time: those are mostly games that were
+        // descheduled by the OS, and they make the numbers jump between otherwise identical runs
+        let mut durations: Vec<Duration> = results.iter().map(|r| r.duration).collect();
+        durations.sort();
+        durations.truncate((durations.len() * 99 / 100).max(1));
+        let trimmed_average = durations.iter().sum::<Duration>() / durations.len() as u32;
+        println!("  -> Total Time:        {:.3}s ({:.0} games/s)", total_time.as_secs_f64(), games as f64 / total_time.as_secs_f64());
+        println!("  -> Time per Game:     {:.1}us (slowest 1% excluded)", trimmed_average.as_secs_f64() * 1e6);
+    }
+
+    if profile {
+        // the factories are called once more only for the names
+        println!("  -> Decision Time:");
+        for (strategy, name) in [p1_factory().name(), p2_factory().name()].iter().enumerate() {
+            let calls: u64 = results.iter().map(|r| r.decide_calls[strategy] as u64).sum();
+            let total: Duration = results.iter().map(|r| r.decide_time[strategy]).sum();
+            let per_move = if calls == 0 { 0 } else { total.as_nanos() / calls as u128 };
+            println!("     P{} [{}]: {} calls, {:.3}s total, {} ns/move", strategy + 1, name, calls, total.as_secs_f64(), per_move);
+        }
+    }
+}
+
+// the n lowest scoring games with their seeds, the lowest first and games with equal scores in the order they were played
+fn worst_games(scored_seeds: &[(u8, u64)], n: usize, replayable: bool) -> Vec<String> {
+    let mut sorted = scored_seeds.to_vec();
+    sorted.sort_by_key(|&(score, _)| score);
+    let mut lines = vec![if replayable {
+        "  -> Worst Games (replay with --single --seed <seed>):".to_string()
+    } else {
+        "  -> Worst Games (--single only replays two player games without rainbow):".to_string()
+    }];
+    lines.extend(sorted.iter().take(n).map(|(score, seed)| format!("     score {:>2}: --seed {}", score, seed)));
+    lines
+}
+
+// percentage of finished games on stderr, updated every 1% so a long benchmark does not look like a hang.
+// stays silent when stderr is not a terminal, and stdout is never touched, so csv and json output stay clean
+struct Progress {
+    done: AtomicU32,
+    total: u32,
+    step: u32, // games per printed percent
+    enabled: bool,
+}
+
+impl Progress {
+    fn new(total: u32) -> Self {
+        Progress { done: AtomicU32::new(0), total, step: (total / 100).max(1), enabled: std::io::stderr().is_terminal() }
+    }
+
+    // called once per finished game, from any thread
+    fn tick(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.enabled && done.is_multiple_of(self.step) {
+            eprint!("\r  {}% ({}/{})", done as u64 * 100 / self.total as u64, done, self.total);
+            let _ = std::io::stderr().flush();
+        }
+    }
+
+    // clears the progress line again
+    fn finish(&self) {
+        if self.enabled {
+            eprint!("\r{}\r", " ".repeat(40));
+        }
+    }
+}
+
+// distribution of the scores of a benchmark
+struct ScoreSummary {
+    mean: f64,
+    stddev: f64, // sample standard deviation
+    median: u8,
+    percentile_5: u8,
+    percentile_95: u8,
+    histogram: Vec<u32>, // number of games at every score from 0 to the max score
+}
+
+impl ScoreSummary {
+    fn new(scores: &[u8], max_score: u8) -> Self {
+        let games = scores.len() as f64;
+        let mean = scores.iter().map(|&s| s as f64).sum::<f64>() / games;
+        let stddev = (scores.iter().map(|&s| (s as f64 - mean).powi(2)).sum::<f64>() / (games - 1.0).max(1.0)).sqrt();
+        let mut histogram = vec![0u32; max_score as usize + 1];
+        for &score in scores {
+            histogram[score as usize] += 1;
+        }
+        let mut sorted = scores.to_vec();
+        sorted.sort_unstable();
+        // nearest rank percentile
+        let percentile = |p: usize| sorted[(sorted.len() * p / 100).min(sorted.len() - 1)];
+        ScoreSummary { mean, stddev, median: percentile(50), percentile_5: percentile(5), percentile_95: percentile(95), histogram }
+    }
+
+    // one line per score between the lowest and the highest one reached, e.g. ` 7: ##### (312)`,
+    // the bars are scaled so the most common score gets 50 #
+    fn print_histogram(&self) {
+        let Some(lowest) = self.histogram.iter().position(|&count| count > 0) else { return };
+        let highest = self.histogram.iter().rposition(|&count| count > 0).unwrap_or(lowest);
+        let most = *self.histogram.iter().max().unwrap_or(&1) as usize;
+        for (score, &count) in self.histogram.iter().enumerate().take(highest + 1).skip(lowest) {
+            let bar = "#".repeat((count as usize * 50).div_ceil(most));
+            println!("     {:>2}: {} ({})", score, bar, count);
+        }
+    }
+}
+
+/// Prints the scores of a benchmark as csv records or as a json summary with
+/// mean, sample standard deviation, perfect and lost games and the number of games at every score
+fn print_scores(results: &[GameStats], max_score: u8, format: OutputFormat, fingerprint: &str) {
+    match format {
+        OutputFormat::Csv => {
+            println!("game_index,score,turns,cards_left");
+            for (i, result) in results.iter().enumerate() {
+                println!("{},{},{},{}", i, result.score, result.turns, result.cards_left);
+            }
+        }
+        OutputFormat::Json => {
+            let scores: Vec<u8> = results.iter().map(|r| r.score).collect();
+            let ScoreSummary { mean, stddev, histogram, .. } = ScoreSummary::new(&scores, max_score);
+            let games = results.len() as f64;
+            let mean_turns = results.iter().map(|r| r.turns as f64).sum::<f64>() / games;
+            let mean_cards_left = results.iter().map(|r| r.cards_left as f64).sum::<f64>() / games;
+            println!("{{\"fingerprint\": \"{}\", \"mean\": {:.4}, \"stddev\": {:.4}, \"perfect\": {}, \"zero\": {}, \"mean_turns\": {:.4}, \"mean_cards_left\": {:.4}, \"histogram\": [{}]}}",
+                fingerprint, mean, stddev, histogram[max_score as usize], histogram[0], mean_turns, mean_cards_left,
+                histogram.iter().map(|count| count.to_string()).collect::<Vec<_>>().join(", "));
+        }
+        OutputFormat::Text => unreachable!("the text output is printed by the benchmark itself"),
+    }
+}
+
+/// Runs the given number of games on each deck composition and prints a table of the results
+fn run_composition_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory, games: u32) {
+    let compositions = [
+        DeckComposition { name: "Standard", keep: |_| true },
+        DeckComposition { name: "No 5s", keep: |c| c.get_value() != 5 },
+        DeckComposition { name: "4 colors", keep: |c| c.get_color() != Color::White },
+    ];
+    println!("Simulating {} games per deck composition...", games);
+    println!("{:<10} {:>5} {:>10} {:>8}", "Deck", "Max", "Avg Score", "Perfect");
+
+    for composition in &compositions {
+        let max_score = composition.max_score();
+        let scores: Vec<u8> = (0..games)
+                        .into_par_iter()
+                        .map(|_| {
+                            let mut deck = Deck::new_with_composition(composition);
+                            deck.shuffle();
+                            let mut game = Game::new_with_deck(vec![Player::new(p1_factory()), Player::new(p2_factory())], GameConfig::for_players(2), deck);
+                            loop {
+                                if let Some(final_score) = game.game_over() {
+                                    return final_score;
+                                }
+                                game.advance();
+                            }
+                        })
+                        .collect();
+        let average_score = scores.iter().map(|&s| s as f64).sum::<f64>() / games as f64;
+        let perfect_games = scores.iter().filter(|&&s| s == max_score).count();
+        println!("{:<10} {:>5} {:>10.4} {:>8}", composition.name, max_score, average_score, perfect_games);
+    }
+}
+
+/// Runs the given number of games for every pair of strategies, self-play included, and prints a matrix of
+/// the average scores. Rows are sorted by the mean over all matchups, strongest first.
+/// A matchup in which a strategy panics is shown as "crash" and left out of the means
+fn run_tournament(strategies: &[(&str, StrategyFactory)], games: u32) {
+    println!("Simulating {} games per matchup...", games);
+    let n = strategies.len();
+    let mut averages: Vec<Vec<Option<f64>>> = vec![vec![None; n]; n];
+    for i in 0..n {
+        for j in i..n {
+            let (p1_factory, p2_factory) = (strategies[i].1, strategies[j].1);
+            let matchup = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                (0..games)
+                    .into_par_iter()
+                    .map(|_| play_game(&mut seat_strategies(p1_factory, p2_factory, 2), GameVariant::Standard, None).score as u32)
+                    .sum::<u32>() as f64 / games as f64
+            }));
+            averages[i][j] = matchup.ok();
+            averages[j][i] = averages[i][j];
+        }
+    }
+
+    let mean = |i: usize| {
+        let finished: Vec<f64> = averages[i].iter().flatten().copied().collect();
+        if finished.is_empty() { None } else { Some(finished.iter().sum::<f64>() / finished.len() as f64) }
+    };
+    // strategies that crashed in every matchup go last
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| mean(b).unwrap_or(f64::NEG_INFINITY).total_cmp(&mean(a).unwrap_or(f64::NEG_INFINITY)));
+
+    print!("\n{:<14}", "");
+    for &j in &order {
+        print!(" {:>12}", strategies[j].0);
+    }
+    println!(" {:>8}", "Mean");
+    for &i in &order {
+        print!("{:<14}", strategies[i].0);
+        for &j in &order {
+            match averages[i][j] {
+                Some(average) => print!(" {:>12.4}", average),
+                None => print!(" {:>12}", "crash"),
+            }
+        }
+        match mean(i) {
+            Some(mean) => println!(" {:>8.4}", mean),
+            None => println!(" {:>8}", "-"),
+        }
+    }
+}
+
+/// Runs the given number of games with each seating order and reports the average score of the first
+/// strategy when it moves first and when it moves second
+fn run_seat_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory, games: u32) {
+    let (p1_name, p2_name) = (p1_factory().name(), p2_factory().name());
+    println!("Simulating {} games per seating...", games);
+
+    let seeds: Vec<u64> = (0..games).map(|_| rand::random()).collect();
+    let (average_as_p1, average_as_p2) = seat_averages(p1_factory, p2_factory, &seeds);
+
+    println!("  -> {} as P1 vs {}: {:.4}", p1_name, p2_name, average_as_p1);
+    println!("  -> {} as P2 vs {}: {:.4}", p1_name, p2_name, average_as_p2);
+    println!("  -> Seat difference:   {:.4}", average_as_p1 - average_as_p2);
+}
+
+// the average score with p1 moving first and with p2 moving first, both seatings dealt from the same seeds
+fn seat_averages(p1_factory: StrategyFactory, p2_factory: StrategyFactory, seeds: &[u64]) -> (f64, f64) {
+    let average = |scores: Vec<u8>| scores.iter().map(|&score| score as u32).sum::<u32>() as f64 / scores.len() as f64;
+    (average(evaluate(p1_factory, p2_factory, seeds)), average(evaluate(p2_factory, p1_factory, seeds)))
+}
+
+/// Plays the same shuffled deck the given number of times, only reseeding the strategies in between.
+/// Deterministic strategies must produce zero spread here.
+fn run_fixed_deck_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory, games: u32) {
+    println!("Simulating {} games on a fixed deck...", games);
+
+    let mut deck = Deck::new_full_deck();
+    deck.shuffle();
+
+    let scores = fixed_deck_scores(p1_factory, p2_factory, &deck, games);
+
+    let min_score = *scores.iter().min().expect("At least one game is simulated");
+    let max_score = *scores.iter().max().expect("At least one game is simulated");
+    let average_score = scores.iter().map(|&s| s as f64).sum::<f64>() / games as f64;
+    let variance = scores.iter().map(|&s| (s as f64 - average_score).powi(2)).sum::<f64>() / games as f64;
+    println!("  -> Average Score:     {:.4}", average_score);
+    println!("  -> Min / Max Score:   {} / {}", min_score, max_score);
+    println!("  -> Std Deviation:     {:.4}", variance.sqrt());
+}
+
+// the scores of the games on this deck, game i with the strategies seeded from i
+fn fixed_deck_scores(p1_factory: StrategyFactory, p2_factory: StrategyFactory, deck: &Deck, games: u32) -> Vec<u8> {
+    (0..games)
+        .into_par_iter()
+        .map(|seed| {
+            let mut p1 = Player::new(p1_factory());
+            let mut p2 = Player::new(p2_factory());
+            // give both seats a different seed so they do not mirror each other
+            p1.strategy.seed_rng(2 * seed as u64);
+            p2.strategy.seed_rng(2 * seed as u64 + 1);
+            let mut game = Game::new_with_deck(vec![p1, p2], GameConfig::for_players(2), deck.clone());
+            loop {
+                if let Some(final_score) = game.game_over() {
+                    return final_score;
+                }
+                game.advance();
+            }
+        })
+        .collect()
+}
+
+/// Runs the given number of games and compares the playability both strategies claimed for their plays with the outcome
+fn run_calibration_benchmark(p1_factory: StrategyFactory, p2_factory: StrategyFactory, games: u32) {
+    println!("Simulating {} games for calibration...", games);
+
+    let calibration = (0..games)
+                    .into_par_iter()
+                    .map(|_| calibration_of_game(Game::new(vec![Player::new(p1_factory()), Player::new(p2_factory())], GameConfig::for_players(2))))
+                    .reduce(CalibrationMetric::new, CalibrationMetric::merge);
+
+    println!("{}", calibration.output());
+}
+
+// plays the game to its end and records the playability claimed for every play against its outcome
+fn calibration_of_game(mut game: Game) -> CalibrationMetric {
+    let mut metric = CalibrationMetric::new();
+    while game.game_over().is_none() {
+        let player_index = game.player_to_move;
+        let selected_move = game.decide_move();
+        // ask before the move is applied, afterwards the strategy knows the card
+        let claimed = match selected_move {
+            Move::Play(idx) => game.players[player_index].strategy.play_probability(idx),
+            _ => None,
+        };
+        let mistakes_before = game.mistakes_made();
+        game.apply_move(selected_move).expect("bot strategy made an illegal move");
+        if let Some(claimed) = claimed {
+            metric.record_play(claimed, game.mistakes_made() == mistakes_before);
+        }
+    }
+    metric
 }
 
 /// Runs a single game and prints step-by-step details
-fn run_single_game(p1_name: &str, p1_factory: StrategyFactory, p2_name: &str, p2_factory: StrategyFactory) {
-    let p1 = Player::new(p1_factory());
-    let p2 = Player::new(p2_factory());
-    let mut game = Game::new(p1, p2);
-    let mut turn_count = 1;
+/// With a seed it is dealt like the benchmark game with that seed
+fn run_single_game(p1_factory: StrategyFactory, p2_factory: StrategyFactory, metrics: &mut [Box<dyn Metric>], debug: bool, hanab_json: bool, seed: Option<u64>) {
+    let players = vec![Player::new(p1_factory()), Player::new(p2_factory())];
+    let mut game = match seed {
+        Some(seed) => Game::new_seeded(players, GameConfig::for_players(2), seed),
+        None => Game::new(players, GameConfig::for_players(2)),
+    };
+    game.add_observer(Box::new(StatusPrinter));
 
+    let (p1_name, p2_name) = (game.players[0].strategy.name(), game.players[1].strategy.name());
     let p1_is_human = p1_name == "Human";
     let p2_is_human = p2_name == "Human";
 
@@ -151,9 +784,21 @@ fn run_single_game(p1_name: &str, p1_factory: StrategyFactory, p2_name: &str, p2
         if let Some(final_score) = game.game_over() {
             println!("\nGame Over!");
             println!("Final Score: {}", final_score);
+            for metric in metrics.iter() {
+                println!("\n{}", metric.output());
+            }
+            if hanab_json {
+                println!("\n{}", game.to_hanab_json());
+            }
+            print_game_logs(&game);
             break;
         }
 
+        let turn_count = game.move_log().len() as u32 + 1;
+        for metric in metrics.iter_mut() {
+            metric.record_turn(turn_count, &game);
+        }
+
         println!("\n---------------------------------------");
         println!("Move {}:", turn_count);
 
@@ -163,33 +808,152 @@ fn run_single_game(p1_name: &str, p1_factory: StrategyFactory, p2_name: &str, p2
         // Before asking for the move, print the game state from the perspective of an observer,
         // BUT hide hands if necessary.
 
-        // Print Player 1
-        print!("Player 1 ({}): ", p1_name);
-        if p1_is_human {
-             println!("[HIDDEN HAND]");
-        } else {
-             println!("{}", game.players[0]);
+        let names = [p1_name, p2_name];
+        for (i, name) in names.iter().enumerate() {
+            if hand_hidden(&names, i, player_index) {
+                println!("Player {} ({}): [HIDDEN HAND]", i + 1, name);
+            } else {
+                println!("Player {} ({}): {}", i + 1, name, game.players[i]);
+            }
         }
 
-        // Print Player 2
-        print!("Player 2 ({}): ", p2_name);
-        if p2_is_human && false{
-             println!("[HIDDEN HAND]");
-        } else {
-             println!("{}", game.players[1]);
-        }
+        println!("Fireworks: \x1b[31m{}\x1b[0m, \x1b[32m{}\x1b[0m, \x1b[34m{}\x1b[0m, \x1b[33m{}\x1b[0m, \x1b[37m{}\x1b[0m", game.fireworks()[0], game.fireworks()[1], game.fireworks()[2], game.fireworks()[3], game.fireworks()[4]);
+        println!("Deck: {} cards left", game.deck.remaining());
+        println!("Discards: {}", CardGroups(game.discard_pile()));
 
-        println!("Fireworks: \x1b[31m{}\x1b[0m, \x1b[32m{}\x1b[0m, \x1b[34m{}\x1b[0m, \x1b[33m{}\x1b[0m, \x1b[37m{}\x1b[0m", game.fireworks[0], game.fireworks[1], game.fireworks[2], game.fireworks[3], game.fireworks[4]);
+        // what each player can deduce about their own hand from the hints alone
+        if debug {
+            for (i, knowledge) in game.hand_knowledge.iter().enumerate() {
+                println!("Player {} knows: {}", i + 1, knowledge.iter().map(|k| k.summary()).collect::<Vec<_>>().join(" "));
+            }
+        }
 
-        let selected_move = game.players[player_index].strategy.decide_move();
+        // a human is asked again until the game accepts the move, bots get no second chance
+        let is_human = if player_index == 0 { p1_is_human } else { p2_is_human };
+        let selected_move = if is_human {
+            loop {
+                let mv = game.ask_strategy();
+                match game.check_move(&mv) {
+                    Ok(()) => break mv,
+                    Err(reason) => println!("That move is not allowed ({:?}), try again.", reason),
+                }
+            }
+        } else {
+            game.decide_move()
+        };
 
         // Print the move chosen
         let current_player_name = if player_index == 0 { p1_name } else { p2_name };
-        println!("{} plays -> {}", current_player_name, format_move(&selected_move, &game));
+        // the status printer finishes the line once the move is applied
+        print!("{} plays -> {}", current_player_name, format_move(&selected_move, &game));
+
+        let deck_was_empty = game.deck.remaining() == 0;
+        game.apply_move(selected_move).expect("bot strategy made an illegal move");
+        if !deck_was_empty && game.deck.remaining() == 0 {
+            println!("The last card was drawn, the final round begins: every player has one more move.");
+        }
+    }
+}
+
+/// Prints the log of every seat whose strategy kept one, like the Observer
+fn print_game_logs(game: &Game) {
+    for (i, player) in game.players.iter().enumerate() {
+        if let Some(log) = player.strategy.game_log() {
+            print!("\nPlayer {} ({}) log:\n{}", i + 1, player.strategy.name(), log);
+        }
+    }
+}
+
+/// Whether the shared screen has to hide this player's hand: a human never sees their own cards.
+/// With two humans the one to move sits at the screen, so only their hand is hidden and they see their partner's
+fn hand_hidden(names: &[&str], player: usize, player_to_move: usize) -> bool {
+    let human_seated_at_screen = names[player_to_move] == "Human" && player_to_move != player;
+    names[player] == "Human" && !human_seated_at_screen
+}
+
+/// Plays one game a move at a time. Before every move the screen is cleared and the table is drawn in a box,
+/// a bot only moves once Enter is pressed. Human hands are hidden like in the single game mode
+/// With a seed it is dealt like the benchmark game with that seed
+fn run_step_game(p1_factory: StrategyFactory, p2_factory: StrategyFactory, seed: Option<u64>) {
+    let players = vec![Player::new(p1_factory()), Player::new(p2_factory())];
+    let mut game = match seed {
+        Some(seed) => Game::new_seeded(players, GameConfig::for_players(2), seed),
+        None => Game::new(players, GameConfig::for_players(2)),
+    };
+    let names = [game.players[0].strategy.name(), game.players[1].strategy.name()];
+    let mut last_move = String::from("-");
+
+    loop {
+        let final_score = game.game_over();
+        let view = game.view();
+        let mut lines = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            // > marks the player to move
+            let marker = if final_score.is_none() && i == game.player_to_move { ">" } else { " " };
+            let hand = if hand_hidden(&names, i, game.player_to_move) { "[HIDDEN HAND]".to_string() } else { game.players[i].to_string() };
+            lines.push(format!("{} Player {} ({}): {}", marker, i + 1, name, hand));
+        }
+        let fireworks: Vec<String> = [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White].iter()
+            .map(|color| format!("{}{}\x1b[0m", color.ansi_code(), view.fireworks[color.index()]))
+            .collect();
+        lines.push(format!("Fireworks: {}", fireworks.join(" ")));
+        lines.push(format!("Discards: {}", CardGroups(&view.discard_pile)));
+        let final_round = if view.deck_remaining == 0 { " (final round)" } else { "" };
+        lines.push(format!("Hints: {}  Strikes: {}  Deck: {}{}", view.hints_remaining, view.mistakes_made, view.deck_remaining, final_round));
+        lines.push(format!("Last move: {}", last_move));
 
-        game.apply_move(selected_move);
-        turn_count += 1;
+        // clear the screen and start at the top left
+        print!("\x1b[2J\x1b[H");
+        println!("{}", framed(&lines));
+
+        if let Some(final_score) = final_score {
+            println!("Game Over! Final Score: {}", final_score);
+            print_game_logs(&game);
+            break;
+        }
+
+        let player_index = game.player_to_move;
+        let selected_move = if names[player_index] == "Human" {
+            loop {
+                let mv = game.ask_strategy();
+                match game.check_move(&mv) {
+                    Ok(()) => break mv,
+                    Err(reason) => println!("That move is not allowed ({:?}), try again.", reason),
+                }
+            }
+        } else {
+            print!("Press Enter for the next move...");
+            std::io::stdout().flush().unwrap();
+            let mut input = String::new();
+            // without input left (e.g. piped in) the game simply runs on
+            let _ = std::io::stdin().read_line(&mut input);
+            game.decide_move()
+        };
+
+        last_move = format!("{} plays {}", names[player_index], format_move(&selected_move, &game));
+        game.apply_move(selected_move).expect("bot strategy made an illegal move");
+    }
+}
+
+// the lines inside a box of + - and |. the color escapes take no room on screen, so they are not counted
+fn framed(lines: &[String]) -> String {
+    let visible_width = |line: &str| {
+        let mut in_escape = false;
+        line.chars().filter(|&c| {
+            if c == '\x1b' { in_escape = true; }
+            let visible = !in_escape;
+            if in_escape && c == 'm' { in_escape = false; }
+            visible
+        }).count()
+    };
+    let width = lines.iter().map(|line| visible_width(line)).max().unwrap_or(0);
+    let border = format!("+{}+", "-".repeat(width + 2));
+    let mut out = format!("{}\n", border);
+    for line in lines {
+        out.push_str(&format!("| {}{} |\n", line, " ".repeat(width - visible_width(line))));
     }
+    out.push_str(&border);
+    out
 }
 
 fn format_move(mv: &Move, game: &Game) -> String {
@@ -207,7 +971,7 @@ fn format_move(mv: &Move, game: &Game) -> String {
             // Berechne die betroffenen Indizes beim ANDEREN Spieler
             let target_idx = if player_idx == 0 { 1 } else { 0 };
             let indices: Vec<usize> = game.players[target_idx].hand.iter().enumerate()
-                .filter(|(_, card)| card.get_color() == *color)
+                .filter(|(_, card)| card.get_color().touched_by(*color))
                 .map(|(i, _)| i)
                 .collect();
             format!("Hint Color {:?} -> Indices {:?}", color, indices.iter().map(|x| x+1).collect::<Vec<_>>())
@@ -224,48 +988,39 @@ fn format_move(mv: &Move, game: &Game) -> String {
     }
 }
 
-fn run_cheater_benchmark() {
-    println!("Simulating {} games (Cheater vs Cheater)...", GAMES_TO_SIMULATE);
+/// Shuffles a deck and prints the draw order together with where the copies of each card ended up
+fn run_deal() {
+    let mut deck = Deck::new_full_deck();
+    deck.shuffle();
 
-    // paralell with rayon
-    let results: Vec<u8> = (0..GAMES_TO_SIMULATE)
-        .into_par_iter()
-        .map(|_| {
-            // Setup Shared States
-            let cheat_state_p1 = Rc::new(RefCell::new(CheatSharedState::default()));
-            let cheat_state_p2 = Rc::new(RefCell::new(CheatSharedState::default()));
+    println!("Draw order (first card drawn first):");
+    for card in deck.cards.iter().rev() {
+        print!("{} ", card);
+    }
+    println!("\n");
 
-            // Setup strategies
-            let strat1 = Box::new(Cheater::new(cheat_state_p1.clone()));
-            let strat2 = Box::new(Cheater::new(cheat_state_p2.clone()));
+    println!("Draw positions per card (BURIED marks all copies in the last {} draws):", BURIED_DRAWS);
+    for (color, value, positions, buried) in deck.draw_positions() {
+        let marker = if buried { "  <- BURIED" } else { "" };
+        println!("  {:?} {}: {:?}{}", color, value, positions.iter().map(|p| p+1).collect::<Vec<_>>(), marker);
+    }
+}
 
-            // setup game
-            let p1 = Player::new(strat1);
-            let p2 = Player::new(strat2);
-            let mut game = Game::new(p1, p2);
+fn run_cheater_benchmark(games: u32) {
+    println!("Simulating {} games (Cheater vs Cheater)...", games);
 
-            // game loop with injection of the illegal information
+    // paralell with rayon
+    let results: Vec<u8> = (0..games)
+        .into_par_iter()
+        .map(|_| {
+            let p1 = Player::new(Box::new(Cheater::new()));
+            let p2 = Player::new(Box::new(Cheater::new()));
+            let mut game = Game::new(vec![p1, p2], GameConfig::for_players(2));
+            // the game shows the cheaters all cards before each of their moves
             loop {
                 if let Some(score) = game.game_over() {
                     return score;
                 }
-                {
-                    let mut s1 = cheat_state_p1.borrow_mut();
-                    s1.my_hand = game.players[0].hand.clone();
-                    s1.partner_hand = game.players[1].hand.clone();
-                    s1.hints_remaining = game.hints_remaining.clone();
-                    s1.fireworks = game.fireworks.clone();
-                    s1.deck_cards = game.deck.cards.clone();
-                }
-                {
-                    let mut s2 = cheat_state_p2.borrow_mut();
-                    s2.my_hand = game.players[1].hand.clone();
-                    s2.partner_hand = game.players[0].hand.clone();
-                    s2.hints_remaining = game.hints_remaining.clone();
-                    s2.fireworks = game.fireworks.clone();
-                    s2.deck_cards = game.deck.cards.clone();
-                }
-
                 game.advance();
             }
         })
@@ -273,10 +1028,103 @@ fn run_cheater_benchmark() {
 
     // Results summary
     let total_score: u32 = results.iter().map(|&s| s as u32).sum();
-    let avg_score = total_score as f64 / GAMES_TO_SIMULATE as f64;
+    let avg_score = total_score as f64 / games as f64;
     let perfect_games = results.iter().filter(|&&s| s == 25).count();
-    let perfect_percent = (perfect_games as f64 / GAMES_TO_SIMULATE as f64) * 100.0;
+    let perfect_percent = (perfect_games as f64 / games as f64) * 100.0;
 
     println!("Average Score: {:.4}", avg_score);
     println!("Perfect Games: {} ({:.2}%)", perfect_games, perfect_percent);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanabi::strategy::Strategy;
+    use hanabi::card::Card;
+    use hanabi::enums::MoveResult;
+    use hanabi::game::GameView;
+
+    // plays its oldest card on its first turn and discards after that, the other seat only ever discards
+    #[derive(Default)]
+    struct PlaysOnce { played: bool }
+    struct AlwaysDiscard;
+
+    impl Strategy for PlaysOnce {
+        fn name(&self) -> &'static str { "PlaysOnce" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) { self.played = false; }
+        fn decide_move(&mut self, _view: &GameView) -> Move {
+            if std::mem::replace(&mut self.played, true) { Move::Discard(0) } else { Move::Play(0) }
+        }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+    }
+
+    // plays its oldest card every turn and claims it is 90% playable
+    struct Overconfident;
+
+    impl Strategy for Overconfident {
+        fn name(&self) -> &'static str { "Overconfident" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) {}
+        fn decide_move(&mut self, _view: &GameView) -> Move { Move::Play(0) }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+        fn play_probability(&self, _idx: usize) -> Option<f64> { Some(0.9) }
+    }
+
+    impl Strategy for AlwaysDiscard {
+        fn name(&self) -> &'static str { "AlwaysDiscard" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) {}
+        fn decide_move(&mut self, _view: &GameView) -> Move { Move::Discard(0) }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+    }
+
+    #[test]
+    fn misspelled_name_suggests_the_closest_strategy() {
+        let names: Vec<&str> = strategies::registry().iter().map(|(name, _)| *name).collect();
+        assert_eq!(closest_name("Geminii", &names), Some("Gemini"));
+    }
+
+    #[test]
+    fn single_strategy_plays_against_itself() {
+        let mut selected = vec!["Gemini"];
+        assert!(seat_for_self_play(&mut selected));
+        assert_eq!(selected, ["Gemini", "Gemini"]);
+        assert!(!seat_for_self_play(&mut selected));
+    }
+
+    // Robert never draws on its rng, so reseeding it can't change a game on the same deck
+    #[test]
+    fn deterministic_strategy_has_no_spread_on_a_fixed_deck() {
+        let robert = || -> Box<dyn Strategy> { Box::new(Robert::new()) };
+        let mut deck = Deck::new_full_deck();
+        deck.shuffle_seeded(7);
+        let scores = fixed_deck_scores(&robert, &robert, &deck, 8);
+        assert!(scores.iter().all(|&score| score == scores[0]), "scores {:?}", scores);
+    }
+
+    // the same deals give different cards to the playing seat once the seats are swapped
+    #[test]
+    fn swapped_seats_score_differently() {
+        let play = || -> Box<dyn Strategy> { Box::new(PlaysOnce::default()) };
+        let discard = || -> Box<dyn Strategy> { Box::new(AlwaysDiscard) };
+        let seeds: Vec<u64> = (0..50).collect();
+        let (as_p1, as_p2) = seat_averages(&play, &discard, &seeds);
+        assert_ne!(as_p1, as_p2);
+        assert_eq!(seat_averages(&play, &discard, &seeds), (as_p1, as_p2));
+    }
+
+    // without 1s in the deck every play fails, the 90% bucket has to report that none succeeded
+    #[test]
+    fn calibration_reports_a_fixed_miscalibration() {
+        let no_ones = || Deck { cards: (0..50).map(Card::new).filter(|card| card.get_value() != 1).collect() };
+        let game = || Game::new_with_deck(vec![Player::new(Box::new(Overconfident)), Player::new(Box::new(Overconfident))], GameConfig::for_players(2), no_ones());
+        // three misplays end each game
+        let metric = calibration_of_game(game()).merge(calibration_of_game(game()));
+        let output = metric.output();
+        assert!(output.lines().any(|line| line == "0.9,1.0,6,0.0000"), "{}", output);
+        assert!(output.lines().skip(1).take(9).all(|line| line.ends_with(",0,0.0000")), "{}", output);
+    }
+}
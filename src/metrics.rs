@@ -0,0 +1,123 @@
+use crate::game::Game;
+
+// A metric is fed the game once per turn, before the player to move decides, and reports a series at the end
+pub trait Metric {
+    fn record_turn(&mut self, turn: u32, game: &Game);
+
+    // csv formatted output, first line is the header
+    fn output(&self) -> String;
+}
+
+// Total belief entropy (in bits) over the hand of the player to move, based on the hints they received
+pub struct EntropyMetric {
+    series: Vec<(u32, usize, f64)>, // turn, player to move, summed entropy over their slots
+}
+
+impl Default for EntropyMetric {
+    fn default() -> Self {
+        EntropyMetric::new()
+    }
+}
+
+impl EntropyMetric {
+    pub fn new() -> Self {
+        EntropyMetric { series: Vec::new() }
+    }
+}
+
+impl Metric for EntropyMetric {
+    fn record_turn(&mut self, turn: u32, game: &Game) {
+        let player = game.player_to_move;
+        let entropy: f64 = game.hand_knowledge[player].iter().map(|k| k.entropy()).sum();
+        self.series.push((turn, player, entropy));
+    }
+
+    fn output(&self) -> String {
+        let mut out = String::from("turn,player,entropy\n");
+        for (turn, player, entropy) in &self.series {
+            out.push_str(&format!("{},{},{:.4}\n", turn, player + 1, entropy));
+        }
+        out
+    }
+}
+
+// number of equally wide buckets the claimed probabilities are sorted into
+const CALIBRATION_BUCKETS: usize = 10;
+
+// How often plays that a strategy claimed to be x% playable actually succeeded.
+// Plays are fed through record_play, so the per turn hook is not needed
+pub struct CalibrationMetric {
+    plays: [u32; CALIBRATION_BUCKETS],
+    successes: [u32; CALIBRATION_BUCKETS],
+}
+
+impl Default for CalibrationMetric {
+    fn default() -> Self {
+        CalibrationMetric::new()
+    }
+}
+
+impl CalibrationMetric {
+    pub fn new() -> Self {
+        CalibrationMetric { plays: [0; CALIBRATION_BUCKETS], successes: [0; CALIBRATION_BUCKETS] }
+    }
+
+    pub fn record_play(&mut self, claimed: f64, success: bool) {
+        // a claimed probability of exactly 1.0 goes into the last bucket
+        let bucket = ((claimed.clamp(0.0, 1.0) * CALIBRATION_BUCKETS as f64) as usize).min(CALIBRATION_BUCKETS - 1);
+        self.plays[bucket] += 1;
+        if success {
+            self.successes[bucket] += 1;
+        }
+    }
+
+    // adds the plays of another metric, used to aggregate over many games
+    pub fn merge(mut self, other: CalibrationMetric) -> Self {
+        for i in 0..CALIBRATION_BUCKETS {
+            self.plays[i] += other.plays[i];
+            self.successes[i] += other.successes[i];
+        }
+        self
+    }
+}
+
+impl Metric for CalibrationMetric {
+    fn record_turn(&mut self, _turn: u32, _game: &Game) {}
+
+    fn output(&self) -> String {
+        let mut out = String::from("claimed_from,claimed_to,plays,actually_playable\n");
+        for i in 0..CALIBRATION_BUCKETS {
+            let rate = if self.plays[i] == 0 { 0.0 } else { self.successes[i] as f64 / self.plays[i] as f64 };
+            out.push_str(&format!("{:.1},{:.1},{},{:.4}\n",
+                i as f64 / CALIBRATION_BUCKETS as f64, (i + 1) as f64 / CALIBRATION_BUCKETS as f64, self.plays[i], rate));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::enums::{Color, Move};
+    use crate::player::Player;
+    use crate::strategies::robert::Robert;
+
+    // a hint rules out possibilities in every slot, so the hinted player's entropy has to drop
+    #[test]
+    fn entropy_drops_after_a_narrowing_hint() {
+        let players = vec![Player::new(Box::new(Robert::new())), Player::new(Box::new(Robert::new()))];
+        let hands = vec![vec![Card::new(0), Card::new(10), Card::new(20), Card::new(30), Card::new(40)], (3..8).map(Card::new).collect()];
+        let mut game = Game::new_with_hands(players, hands);
+        let mut metric = EntropyMetric::new();
+        metric.record_turn(0, &game);
+        game.apply_move(Move::HintValue(2)).expect("player 2 holds 2s");
+        game.apply_move(Move::HintColor(Color::Red)).expect("player 1 holds a red card");
+        metric.record_turn(2, &game);
+
+        let (_, before_player, before) = metric.series[0];
+        let (_, after_player, after) = metric.series[1];
+        assert_eq!((before_player, after_player), (0, 0));
+        assert!(after < before, "entropy went from {} to {}", before, after);
+    }
+}
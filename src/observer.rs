@@ -0,0 +1,49 @@
+use crate::enums::Move;
+use crate::game::GameView;
+
+// Gets told about every move after the game applied it. Observers only see a read-only view,
+// so any number of them (logging, metrics, a UI) can watch a game without affecting it
+pub trait GameObserver {
+    fn on_move(&mut self, player: usize, mv: &Move, view: &GameView);
+}
+
+// appends the counters after a move to the move line printed by the single game mode, like (hints: 3, strikes: 1, deck: 20)
+pub struct StatusPrinter;
+
+impl StatusPrinter {
+    // the counters as they are appended, with the leading space
+    pub fn status(view: &GameView) -> String {
+        format!(" (hints: {}, strikes: {}, deck: {})", view.hints_remaining, view.mistakes_made, view.deck_remaining)
+    }
+}
+
+impl GameObserver for StatusPrinter {
+    fn on_move(&mut self, _player: usize, _mv: &Move, view: &GameView) {
+        println!("{}", StatusPrinter::status(view));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::enums::Color;
+    use crate::game::Game;
+    use crate::player::Player;
+    use crate::strategies::robert::Robert;
+
+    #[test]
+    fn status_follows_hints_and_strikes() {
+        let players = vec![Player::new(Box::new(Robert::new())), Player::new(Box::new(Robert::new()))];
+        let hands = vec![(0..5).map(Card::new).collect(), (13..18).map(Card::new).collect()];
+        let mut game = Game::new_with_hands(players, hands);
+        game.deck.cards = vec![Card::new(40), Card::new(41)];
+        assert_eq!(StatusPrinter::status(&game.view()), " (hints: 8, strikes: 0, deck: 2)");
+
+        game.apply_move(Move::HintColor(Color::Green)).unwrap();
+        assert_eq!(StatusPrinter::status(&game.view()), " (hints: 7, strikes: 0, deck: 2)");
+        // the partner plays a green 2 on an empty firework and draws
+        game.apply_move(Move::Play(0)).unwrap();
+        assert_eq!(StatusPrinter::status(&game.view()), " (hints: 7, strikes: 1, deck: 1)");
+    }
+}
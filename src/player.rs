@@ -2,6 +2,8 @@ use std::fmt;
 use crate::card::Card;
 use crate::deck::Deck;
 use crate::strategy::Strategy;
+use crate::enums::Move;
+use crate::game::GameView;
 
 pub struct Player {
     pub hand: Vec<Card>,
@@ -21,6 +23,16 @@ impl Player {
         self.hand.push(new_card);
         new_card
     }
+
+    // asks the strategy for its move and checks that play/discard indices point into the hand,
+    // so a buggy strategy is caught here instead of deep inside the game engine
+    pub fn decide_move(&mut self, view: &GameView) -> Move {
+        let mv = self.strategy.decide_move(view);
+        if let Move::Play(idx) | Move::Discard(idx) = mv {
+            debug_assert!(idx < self.hand.len(), "Strategy returned {:?} for a hand of {} cards", mv, self.hand.len());
+        }
+        mv
+    }
 }
 
 impl fmt::Display for Player {
@@ -31,3 +43,32 @@ impl fmt::Display for Player {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::MoveResult;
+    use crate::game::{Game, GameConfig};
+
+    // always plays the slot just past a full hand
+    struct OffByOne;
+
+    impl Strategy for OffByOne {
+        fn name(&self) -> &'static str { "OffByOne" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) {}
+        fn decide_move(&mut self, view: &GameView) -> Move { Move::Play(view.hand_size(0)) }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Strategy returned Play(5) for a hand of 5 cards")]
+    fn out_of_range_index_is_caught_when_deciding() {
+        let players = vec![Player::new(Box::new(OffByOne)), Player::new(Box::new(OffByOne))];
+        let hands = vec![(0..5).map(Card::new).collect(), (10..15).map(Card::new).collect()];
+        let mut game = Game::new_with_hands(players, hands);
+        game.decide_move();
+    }
+}
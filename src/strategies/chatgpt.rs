@@ -2,60 +2,92 @@ use crate::enums::{Move, MoveResult, Color};
 use crate::card::Card;
 use crate::strategy::Strategy;
 use crate::decksubset::DeckSubset;
+use crate::strategy_utils::{apply_own_card_move, apply_card_result, chop_index, concerns_partner_or_me, is_known_duplicate, resolved_hand, safe_fallback_move, best_hint_for_slot, hint_touching};
+use crate::game::{GameConfig, GameView};
+use crate::conventions::{is_truthful, plays_conventions, read_hint, touched_slots};
+
+// with this many cards or fewer left in the deck a discarded card can no longer be replaced in time
+const ENDGAME_DECK_SIZE: usize = 3;
 
 /// ChatGPT strategy inspired by Gemini but slightly simpler.
 ///
 /// Key ideas:
 /// - Track per-slot knowledge using `DeckSubset` for own and partner hands.
-/// - Track `fireworks` and discarded cards to compute playability/criticality.
+/// - Track `fireworks` and read the discard pile from the view to compute playability/criticality.
 /// - Prioritize: play certain cards; give play-enabling hints; save critical partner cards; setup near-future; discard safely.
 pub struct ChatGPT {
     hints_remaining: u8,
-    fireworks: [u8; 5],
+    max_hints: u8, // the starting hint tokens, a discard never regains more
+    fireworks: [u8; 6],
     my_hand_knowledge: Vec<DeckSubset>,
     partner_hand: Vec<Card>,
     partner_hand_knowledge: Vec<DeckSubset>,
     public_unknowns: DeckSubset,
-    discarded_cards: Vec<Card>,
+    deck_remaining: usize,
     last_hint_value: Option<u8>,
     last_hint_color: Option<Color>,
+    number_of_players: usize,
+    conventions: bool, // the partner gives and reads hints through the shared conventions (see negotiate)
+}
+
+impl Default for ChatGPT {
+    fn default() -> Self {
+        ChatGPT::new()
+    }
 }
 
 impl ChatGPT {
     pub fn new() -> Self {
         ChatGPT {
             hints_remaining: 8,
-            fireworks: [0; 5],
+            max_hints: 8,
+            fireworks: [0; 6],
             my_hand_knowledge: Vec::new(),
             partner_hand: Vec::new(),
             partner_hand_knowledge: Vec::new(),
             public_unknowns: DeckSubset::new_full(),
-            discarded_cards: Vec::new(),
+            deck_remaining: 40,
             last_hint_value: None,
             last_hint_color: None,
+            number_of_players: 2,
+            conventions: true,
+        }
+    }
+
+    // a play or discard of a player that is not our partner, or a hint that is for neither of us
+    fn update_after_third_party_move(&mut self, mv_result: &MoveResult) {
+        match mv_result {
+            MoveResult::Play { drawn, .. } | MoveResult::Discard { drawn, .. } => {
+                if let Some((card, _)) = apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    self.public_unknowns.remove_card(&card);
+                }
+                if let Some(new_card) = drawn { self.public_unknowns.remove_card(new_card); }
+            }
+            MoveResult::Hint { .. } => self.hints_remaining = self.hints_remaining.saturating_sub(1),
         }
     }
 
     fn is_playable(&self, card: &Card) -> bool {
-        let idx = card.get_color() as usize;
+        let idx = card.get_color().index();
         self.fireworks[idx] + 1 == card.get_value()
     }
 
     fn is_dead(&self, card: &Card) -> bool {
-        let idx = card.get_color() as usize;
+        let idx = card.get_color().index();
         self.fireworks[idx] >= card.get_value()
     }
 
-    fn count_in_discard(&self, card: &Card) -> usize {
-        self.discarded_cards.iter().filter(|&c| c.get_color() == card.get_color() && c.get_value() == card.get_value()).count()
+    // the discard pile is read from the view of the current move
+    fn count_in_discard(card: &Card, discard_pile: &[Card]) -> usize {
+        discard_pile.iter().filter(|&c| c.get_color() == card.get_color() && c.get_value() == card.get_value()).count()
     }
 
-    fn is_critical(&self, card: &Card) -> bool {
+    fn is_critical(&self, card: &Card, discard_pile: &[Card]) -> bool {
         if self.is_dead(card) { return false; }
         let v = card.get_value();
         if v == 5 { return true; }
-        let copies = self.count_in_discard(card);
-        let max = match v { 1 => 3, 2 | 3 | 4 => 2, _ => 1 };
+        let copies = Self::count_in_discard(card, discard_pile);
+        let max = card.max_copies() as usize;
         copies + 1 >= max
     }
 
@@ -91,6 +123,13 @@ impl ChatGPT {
         any
     }
 
+    // true if the slot may hold a card that is playable now or right after the next play.
+    // in the endgame such a card is usually lost for good when it is discarded
+    fn slot_may_be_needed_soon(&self, idx: usize) -> bool {
+        let poss = self.my_hand_knowledge[idx].intersect(&self.public_unknowns);
+        poss.iter_cards().any(|c| !self.is_dead(&c) && c.get_value() <= self.fireworks[c.get_color().index()] + 2)
+    }
+
     fn calculate_expected_distance(&self, idx: usize) -> f32 {
         if idx >= self.my_hand_knowledge.len() { return 999.0; }
         let poss = self.my_hand_knowledge[idx].intersect(&self.public_unknowns);
@@ -99,7 +138,7 @@ impl ChatGPT {
             let c = Card::new(i);
             if poss.has_card(&c) {
                 total += 1;
-                let color_idx = c.get_color() as usize;
+                let color_idx = c.get_color().index();
                 let val = c.get_value();
                 if self.fireworks[color_idx] >= val { sum += 10; }
                 else { sum += (val - (self.fireworks[color_idx] + 1)) as usize; }
@@ -111,32 +150,53 @@ impl ChatGPT {
 }
 
 impl Strategy for ChatGPT {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>) {
-        self.hints_remaining = 8;
-        self.fireworks = [0; 5];
+    fn name(&self) -> &'static str { "ChatGPT" }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        let hand_size = other_player_hands[0].len();
+        self.number_of_players = other_player_hands.len() + 1;
+        self.hints_remaining = config.starting_hints;
+        self.max_hints = config.starting_hints;
+        self.fireworks = [0; 6];
         self.public_unknowns = DeckSubset::new_full();
-        self.discarded_cards.clear();
-        self.my_hand_knowledge = vec![DeckSubset::new_full(); 5];
-        self.partner_hand = other_player_hand.clone();
-        self.partner_hand_knowledge = vec![DeckSubset::new_full(); 5];
-        for c in other_player_hand { self.public_unknowns.remove_card(c); }
+        self.my_hand_knowledge = vec![DeckSubset::new_full(); hand_size];
+        self.partner_hand = other_player_hands[0].clone();
+        self.partner_hand_knowledge = vec![DeckSubset::new_full(); hand_size];
+        for c in other_player_hands.iter().flatten() { self.public_unknowns.remove_card(c); }
+    }
+
+    fn reset(&mut self) {
+        *self = ChatGPT::new();
     }
 
-    fn decide_move(&mut self) -> Move {
+    // hints are only read through the conventions if the partner gives them that way too
+    fn negotiate(&mut self, partner_name: &str) {
+        self.conventions = plays_conventions(partner_name);
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+        self.hints_remaining = view.hints_remaining;
+        self.fireworks = view.fireworks;
+        self.deck_remaining = view.deck_remaining;
+        debug_assert_eq!(self.partner_hand.len(), view.hand_size(1), "ChatGPT lost track of the partner hand");
         // 1. Play certain
         for i in (0..self.my_hand_knowledge.len()).rev() {
             if self.is_slot_certainly_playable(i) { return Move::Play(i); }
         }
 
         // 2. Save clue: protect critical card in partner's chop (avoid hinting criticals everywhere)
-        if self.hints_remaining > 0 && !self.partner_hand.is_empty() {
-            let chop_idx = if self.partner_hand.len() == 0 { 0 } else { self.partner_hand.len()-1 };
+        if self.hints_remaining > 0 && let Some(chop_idx) = chop_index(&self.partner_hand_knowledge) {
             let chop = self.partner_hand[chop_idx];
-            if self.is_critical(&chop) && (self.last_hint_value != Some(chop.get_value())) {
+            if self.is_critical(&chop, view.discard_pile) && (self.last_hint_value != Some(chop.get_value())) {
                 return Move::HintValue(chop.get_value());
             }
         }
 
+        // hints the partner would read as a play of an unplayable card are never given,
+        // a partner that doesn't play the conventions only reads what a hint says about the cards
+        let truthful = |hint: &Move| !self.conventions || is_truthful(hint, &self.partner_hand_knowledge, &self.partner_hand, &self.fireworks);
+
         // 3. Play-clue: give hints that immediately cause partner to play
         if self.hints_remaining > 0 {
                     for target in 1..=5u8 {
@@ -144,20 +204,19 @@ impl Strategy for ChatGPT {
                     if card.get_value() != target { continue; }
                     if !self.is_playable(card) { continue; }
                     if self.knowledge_implies_playable(&self.partner_hand_knowledge[i]) { continue; }
-                    // color
-                    let k_col = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color(card.get_color()));
-                            if k_col.0 != self.partner_hand_knowledge[i].0 && self.knowledge_implies_playable(&k_col) {
-                                if Some(card.get_color()) != self.last_hint_color {
-                                    return Move::HintColor(card.get_color());
-                                }
-                            }
-                    // value
-                    let k_val = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value(card.get_value()));
-                            if k_val.0 != self.partner_hand_knowledge[i].0 && self.knowledge_implies_playable(&k_val) {
-                                if Some(card.get_value()) != self.last_hint_value {
-                                    return Move::HintValue(card.get_value());
-                                }
-                            }
+                    let best_hint = best_hint_for_slot(&self.partner_hand, &self.partner_hand_knowledge, i, &self.fireworks, &self.public_unknowns);
+                    for hint in best_hint.into_iter().chain([hint_touching(card), Move::HintValue(card.get_value())]) {
+                        let repeated = match hint {
+                            Move::HintColor(c) => Some(c) == self.last_hint_color,
+                            Move::HintValue(v) => Some(v) == self.last_hint_value,
+                            _ => false,
+                        };
+                        // what the partner will know, with the meaning of the hint
+                        let knowledge = read_hint(self.conventions, &hint, &self.partner_hand_knowledge, &touched_slots(&hint, &self.partner_hand), &self.fireworks);
+                        if !repeated && truthful(&hint) && self.knowledge_implies_playable(&knowledge[i]) {
+                            return hint;
+                        }
+                    }
                 }
             }
 
@@ -165,9 +224,10 @@ impl Strategy for ChatGPT {
             if self.hints_remaining > 4 {
                         for (i, card) in self.partner_hand.iter().enumerate() {
                             if self.partner_hand_knowledge[i].0 == DeckSubset::new_full().0 {
-                                let dist = if self.fireworks[card.get_color() as usize] >= card.get_value() { 255 } else { card.get_value() - (self.fireworks[card.get_color() as usize] + 1) };
-                                if (self.is_critical(card) && i >= self.partner_hand.len().saturating_sub(2)) || dist <= 1 {
-                                    if Some(card.get_value()) != self.last_hint_value { return Move::HintValue(card.get_value()); }
+                                let dist = if self.fireworks[card.get_color().index()] >= card.get_value() { 255 } else { card.get_value() - (self.fireworks[card.get_color().index()] + 1) };
+                                if (self.is_critical(card, view.discard_pile) && i >= self.partner_hand.len().saturating_sub(2)) || dist <= 1 {
+                                    let hint = Move::HintValue(card.get_value());
+                                    if Some(card.get_value()) != self.last_hint_value && truthful(&hint) { return hint; }
                                 }
                             }
                         }
@@ -178,65 +238,61 @@ impl Strategy for ChatGPT {
         if self.hints_remaining <= 4 {
             // A: certain dead
             for i in 0..self.my_hand_knowledge.len() { if self.is_slot_certainly_dead(i) { return Move::Discard(i); } }
+            // A2: a known card we or the partner hold another copy of
+            let resolved = resolved_hand(&self.my_hand_knowledge, &self.public_unknowns);
+            for i in 0..resolved.len() { if is_known_duplicate(&resolved, i, &self.partner_hand) { return Move::Discard(i); } }
+            // in the endgame slots that may be needed soon are kept, a stalling hint is better if we still have one
+            let endgame = self.deck_remaining <= ENDGAME_DECK_SIZE;
+            let keep = |i: usize| endgame && self.hints_remaining > 0 && self.slot_may_be_needed_soon(i);
             // B: unhinted chop
-            for i in 0..self.my_hand_knowledge.len() { if self.my_hand_knowledge[i].0 == DeckSubset::new_full().0 { return Move::Discard(i); } }
+            for i in 0..self.my_hand_knowledge.len() { if self.my_hand_knowledge[i].0 == DeckSubset::new_full().0 && !keep(i) { return Move::Discard(i); } }
             // C: panic: discard furthest
-            let mut best_idx = 0usize; let mut best_dist = -1.0f32;
-            for i in 0..self.my_hand_knowledge.len() { let d = self.calculate_expected_distance(i); if d > best_dist { best_dist = d; best_idx = i; } }
-            return Move::Discard(best_idx);
+            let mut best_idx = None; let mut best_dist = -1.0f32;
+            for i in 0..self.my_hand_knowledge.len() { let d = self.calculate_expected_distance(i); if d > best_dist && !keep(i) { best_dist = d; best_idx = Some(i); } }
+            if let Some(best_idx) = best_idx { return Move::Discard(best_idx); }
         }
 
         // 6. Force hint
         if !self.partner_hand.is_empty() {
                     for (i, card) in self.partner_hand.iter().enumerate() {
                         let k_val = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value(card.get_value()));
-                        if k_val.0 != self.partner_hand_knowledge[i].0 && Some(card.get_value()) != self.last_hint_value { return Move::HintValue(card.get_value()); }
+                        if k_val.0 != self.partner_hand_knowledge[i].0 && Some(card.get_value()) != self.last_hint_value && truthful(&Move::HintValue(card.get_value())) { return Move::HintValue(card.get_value()); }
                         let k_col = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color(card.get_color()));
-                        if k_col.0 != self.partner_hand_knowledge[i].0 && Some(card.get_color()) != self.last_hint_color { return Move::HintColor(card.get_color()); }
+                        if k_col.0 != self.partner_hand_knowledge[i].0 && Some(card.get_color()) != self.last_hint_color && truthful(&hint_touching(card)) { return hint_touching(card); }
                     }
             return Move::HintValue(self.partner_hand[self.partner_hand.len()-1].get_value());
         }
 
-        Move::Discard(0)
+        safe_fallback_move(self.my_hand_knowledge.len(), &self.partner_hand, self.hints_remaining, view.discard_allowed)
     }
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
         match mv {
             Move::Play(idx) | Move::Discard(idx) => {
-                if *idx < self.my_hand_knowledge.len() { self.my_hand_knowledge.remove(*idx); }
-                if got_new_card { self.my_hand_knowledge.push(DeckSubset::new_full()); }
-                match mv_result {
-                    MoveResult::Play(success, card, _new_card) => { if *success { self.fireworks[card.get_color() as usize] += 1; } else { self.discarded_cards.push(*card); } }
-                    MoveResult::Discard(card, _new_card) => { self.discarded_cards.push(*card); if self.hints_remaining < 8 { self.hints_remaining += 1; } }
-                    MoveResult::Hint(_) => { /* not expected here for play/discard results */ }
-                }
+                apply_own_card_move(&mut self.my_hand_knowledge, *idx, got_new_card.then(DeckSubset::new_full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints);
             }
             Move::HintColor(c) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 self.last_hint_color = Some(*c);
                 self.last_hint_value = None;
-                let mut hinted = Vec::new();
-                for (i, card) in self.partner_hand.iter().enumerate() { if card.get_color() == *c { hinted.push(i); } }
-                for i in 0..self.partner_hand_knowledge.len() {
-                    if hinted.contains(&i) { self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color(*c)); }
-                    else { self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color_inverted(*c)); }
-                }
             }
             Move::HintValue(v) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 self.last_hint_value = Some(*v);
                 self.last_hint_color = None;
-                let mut hinted = Vec::new();
-                for (i, card) in self.partner_hand.iter().enumerate() { if card.get_value() == *v { hinted.push(i); } }
-                for i in 0..self.partner_hand_knowledge.len() {
-                    if hinted.contains(&i) { self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value(*v)); }
-                    else { self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value_inverted(*v)); }
-                }
             }
         }
+        // the partner reads the hint the way we agreed on, so its knowledge is updated the same way
+        if let (Move::HintColor(_) | Move::HintValue(_), MoveResult::Hint { indices }) = (mv, mv_result) {
+            self.partner_hand_knowledge = read_hint(self.conventions, mv, &self.partner_hand_knowledge, indices, &self.fireworks);
+        }
     }
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult) {
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        if !concerns_partner_or_me(player_offset, self.number_of_players, mv) {
+            self.update_after_third_party_move(mv_result);
+            return;
+        }
         match mv {
             Move::Play(idx) | Move::Discard(idx) => {
                 if *idx < self.partner_hand.len() {
@@ -244,22 +300,21 @@ impl Strategy for ChatGPT {
                     self.partner_hand_knowledge.remove(*idx);
                     self.public_unknowns.remove_card(&card);
                     match mv_result {
-                        MoveResult::Play(success, _, _new_card) => { if *success { self.fireworks[card.get_color() as usize] += 1; } else { self.discarded_cards.push(card); } }
-                        MoveResult::Discard(_, _new_card) => { self.discarded_cards.push(card); if self.hints_remaining < 8 { self.hints_remaining += 1; } }
-                        MoveResult::Hint(_) => { /* not expected here */ }
+                        MoveResult::Play { success, .. } => { if *success { self.fireworks[card.get_color().index()] += 1; } }
+                        MoveResult::Discard { .. } => { if self.hints_remaining < self.max_hints { self.hints_remaining += 1; } }
+                        MoveResult::Hint { .. } => { /* not expected here */ }
                     }
                 }
-            }
-            Move::HintColor(c) => {
-                self.hints_remaining -= 1;
-                if let MoveResult::Hint(indices) = mv_result {
-                    for &i in indices { if i < self.my_hand_knowledge.len() { self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_color(*c)); } }
+                if let MoveResult::Play { drawn: Some(new_card), .. } | MoveResult::Discard { drawn: Some(new_card), .. } = mv_result {
+                    self.partner_hand.push(*new_card);
+                    self.partner_hand_knowledge.push(DeckSubset::new_full());
                 }
             }
-            Move::HintValue(v) => {
-                self.hints_remaining -= 1;
-                if let MoveResult::Hint(indices) = mv_result {
-                    for &i in indices { if i < self.my_hand_knowledge.len() { self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_value(*v)); } }
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
+                // untouched slots (all of them for an empty hint) learn they don't have the color or value
+                if let MoveResult::Hint { indices } = mv_result {
+                    self.my_hand_knowledge = read_hint(self.conventions, mv, &self.my_hand_knowledge, indices, &self.fireworks);
                 }
             }
         }
@@ -1,39 +1,45 @@
 use crate::strategy::Strategy;
 use crate::card::Card;
 use crate::enums::*;
-use std::cell::RefCell;
-use std::rc::Rc;
+use crate::game::{GameConfig, GameView, FullGameState};
+use crate::strategy_utils::{safe_fallback_move, hint_touching};
 
-// Shared state populated by main.rs before every move
+// the cards copied from the full game state the game shows us before every move
 #[derive(Default, Clone)]
-pub struct CheatSharedState {
+pub struct CheatState {
     pub my_hand: Vec<Card>,
     pub partner_hand: Vec<Card>,
     pub deck_cards: Vec<Card>,
-    pub fireworks: [u8; 5],
+    pub fireworks: [u8; 6],
     pub hints_remaining: u8,
 }
 
 pub struct Cheater {
-    pub shared_state: Rc<RefCell<CheatSharedState>>,
+    pub state: CheatState,
+}
+
+impl Default for Cheater {
+    fn default() -> Self {
+        Cheater::new()
+    }
 }
 
 impl Cheater {
-    pub fn new(shared_state: Rc<RefCell<CheatSharedState>>) -> Self {
-        Cheater { shared_state }
+    pub fn new() -> Self {
+        Cheater { state: CheatState::default() }
     }
 
     // ------------------------------------------------------------------------
     // Helper Functions
     // ------------------------------------------------------------------------
 
-    fn is_playable(card: &Card, fireworks: &[u8; 5]) -> bool {
-        let color_idx = card.get_color() as usize;
+    fn is_playable(card: &Card, fireworks: &[u8; 6]) -> bool {
+        let color_idx = card.get_color().index();
         card.get_value() == fireworks[color_idx] + 1
     }
 
-    fn is_dead(card: &Card, fireworks: &[u8; 5]) -> bool {
-        let color_idx = card.get_color() as usize;
+    fn is_dead(card: &Card, fireworks: &[u8; 6]) -> bool {
+        let color_idx = card.get_color().index();
         card.get_value() <= fireworks[color_idx]
     }
 
@@ -42,19 +48,19 @@ impl Cheater {
     /// 1 = Duplicate in own hand (Safe to discard)
     /// 2 = Copy exists in Deck or Partner Hand (Safe-ish)
     /// 3 = Critical (Last copy in game) - Dangerous
-    fn get_discard_score(card: &Card, my_hand: &[Card], partner_hand: &[Card], deck: &[Card], fireworks: &[u8; 5]) -> u8 {
+    fn get_discard_score(card: &Card, my_hand: &[Card], partner_hand: &[Card], deck: &[Card], fireworks: &[u8; 6]) -> u8 {
         if Self::is_dead(card, fireworks) {
             return 0;
         }
 
         // Duplicate in own hand?
-        if my_hand.iter().filter(|c| c == &card).count() > 1 {
+        if my_hand.iter().filter(|c| c.same_kind(card)).count() > 1 {
             return 1;
         }
 
         // Duplicate elsewhere?
-        let in_partner = partner_hand.iter().filter(|c| c == &card).count();
-        let in_deck = deck.iter().filter(|c| c == &card).count();
+        let in_partner = partner_hand.iter().filter(|c| c.same_kind(card)).count();
+        let in_deck = deck.iter().filter(|c| c.same_kind(card)).count();
         if in_partner + in_deck > 0 {
             return 2;
         }
@@ -64,7 +70,7 @@ impl Cheater {
 
     /// Finds the best card to discard from a given hand.
     /// Returns (index, score).
-    fn find_best_discard(hand: &[Card], partner_hand: &[Card], deck: &[Card], fireworks: &[u8; 5]) -> (usize, u8) {
+    fn find_best_discard(hand: &[Card], partner_hand: &[Card], deck: &[Card], fireworks: &[u8; 6]) -> (usize, u8) {
         let mut best_idx = 0;
         let mut best_score = 4; // Worse than max (3)
 
@@ -79,20 +85,28 @@ impl Cheater {
     }
 
     /// Generates a valid hint move to pass the turn.
-    fn get_stall_move(partner_hand: &[Card]) -> Move {
-        if let Some(c) = partner_hand.first() {
-            Move::HintColor(c.get_color())
-        } else {
-            Move::HintValue(1)
+    /// Falls back to a discard if no hint is possible (no hints left or partner holds no cards),
+    /// or to a play if the rules forbid that discard too.
+    fn get_stall_move(state: &CheatState, discard_allowed: bool) -> Move {
+        match state.partner_hand.first() {
+            Some(c) if state.hints_remaining > 0 => hint_touching(c),
+            _ => safe_fallback_move(state.my_hand.len(), &state.partner_hand, state.hints_remaining, discard_allowed),
         }
     }
 }
 
 impl Strategy for Cheater {
-    fn initialize(&mut self, _other_player_hand: &Vec<Card>) {}
+    fn name(&self) -> &'static str { "Cheater" }
 
-    fn decide_move(&mut self) -> Move {
-        let state = self.shared_state.borrow();
+    fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+
+    fn reset(&mut self) {
+        *self = Cheater::new();
+    }
+
+    // the full state seen in observe_full_state already holds everything the view has, except the rules
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        let state = &self.state;
 
         // -----------------------------------------------------------
         // 1. IMMEDIATE PLAY (Priority #1)
@@ -130,12 +144,12 @@ impl Strategy for Cheater {
         // -----------------------------------------------------------
         // If deck is empty, we can't discard (can't draw). We must Hint.
         if deck_empty {
-            return Self::get_stall_move(&state.partner_hand);
+            return Self::get_stall_move(state, view.discard_allowed);
         }
 
         // If hints are full (8), we shouldn't discard (wasteful). We Hint.
         if state.hints_remaining == 8 {
-            return Self::get_stall_move(&state.partner_hand);
+            return Self::get_stall_move(state, view.discard_allowed);
         }
 
         // -----------------------------------------------------------
@@ -147,7 +161,7 @@ impl Strategy for Cheater {
         // A. Stall if Partner can play
         // Giving a hint costs 0 deck cards. It allows partner to score.
         if partner_can_play {
-            return Self::get_stall_move(&state.partner_hand);
+            return Self::get_stall_move(state, view.discard_allowed);
         }
 
         // B. "Pass the Buck" (Who has the safer discard?)
@@ -166,17 +180,25 @@ impl Strategy for Cheater {
             // we should NOT discard. We Hint to stall death.
             // We know hints > 0 here because of check #2.
             if my_discard_score == 3 {
-                return Self::get_stall_move(&state.partner_hand);
+                return Self::get_stall_move(state, view.discard_allowed);
             }
 
             return Move::Discard(my_discard_idx);
         } else {
             // Partner has a safer discard (e.g. I have score 3, he has 0).
             // I Hint to pass the turn to him.
-            return Self::get_stall_move(&state.partner_hand);
+            return Self::get_stall_move(state, view.discard_allowed);
         }
     }
 
+    fn observe_full_state(&mut self, state: &FullGameState) {
+        self.state.my_hand = state.my_hand.to_vec();
+        self.state.partner_hand = state.other_hands[0].to_vec();
+        self.state.deck_cards = state.deck.to_vec();
+        self.state.fireworks = state.fireworks;
+        self.state.hints_remaining = state.hints_remaining;
+    }
+
     fn update_after_own_move(&mut self, _mv: &Move, _res: &MoveResult, _new: bool) {}
-    fn update_after_other_player_move(&mut self, _mv: &Move, _res: &MoveResult) {}
+    fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _res: &MoveResult) {}
 }
\ No newline at end of file
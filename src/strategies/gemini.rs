@@ -2,6 +2,93 @@ use crate::enums::{Move, MoveResult, Color};
 use crate::card::Card;
 use crate::strategy::Strategy;
 use crate::decksubset::DeckSubset;
+use crate::strategy_utils::{apply_own_card_move, apply_card_result, chop_index, concerns_partner_or_me, is_known_duplicate, legal_hints, resolved_hand, safe_fallback_move};
+use crate::game::{GameConfig, GameView};
+use crate::conventions::{is_truthful, plays_conventions, read_hint};
+use crate::config::resolve_config_path;
+use std::fs;
+use std::sync::OnceLock;
+
+/// Tunable thresholds and weights of the Gemini strategy. The defaults are the values Gemini was written with,
+/// gemini_params.txt can override any of them with `key = value` lines.
+#[derive(Clone, Copy, Debug)]
+pub struct GeminiParams {
+    /// Setup clues are only given with more hints than this left.
+    pub setup_clue_min_hints: u8,
+    /// Setup clues are only given for cards at most this many steps away from being playable.
+    pub setup_clue_max_distance: u8,
+    /// Discard score penalty for a slot that is certainly critical, scaled by the probability.
+    pub discard_critical_penalty: f32,
+    /// Discard score of a slot that received a hint.
+    pub discard_hinted_score: i32,
+    /// Distance counted for a useless card when averaging the distance of a slot.
+    pub discard_useless_distance: usize,
+    /// With this many cards or fewer left in the deck a discarded card can no longer be replaced in time.
+    pub endgame_deck_size: usize,
+    /// Discard score penalty in the endgame for a slot that may be a card needed soon, scaled by the probability.
+    pub discard_endgame_penalty: f32,
+    /// Weight of each card a play clue makes known as playable.
+    pub clue_playable_weight: f64,
+    /// Penalty for each useless card a play clue touches. Below 1/hand size it only breaks ties between equally playable clues.
+    pub clue_useless_penalty: f64,
+    /// Save clues are only given with more hints than this left.
+    pub save_clue_min_hints: u8,
+}
+
+impl Default for GeminiParams {
+    fn default() -> Self {
+        GeminiParams {
+            setup_clue_min_hints: 1,
+            setup_clue_max_distance: 1,
+            discard_critical_penalty: 5000.0,
+            discard_hinted_score: -1000,
+            discard_useless_distance: 20,
+            endgame_deck_size: 3,
+            discard_endgame_penalty: 2000.0,
+            clue_playable_weight: 1.0,
+            clue_useless_penalty: 0.1,
+            save_clue_min_hints: 0,
+        }
+    }
+}
+
+impl GeminiParams {
+    // sets the field called key to the parsed value, unknown keys or unparsable values are ignored
+    fn set_from_str(&mut self, key: &str, val_str: &str) {
+        macro_rules! update {
+            ($field:ident) => {
+                if key == stringify!($field) {
+                    if let Ok(v) = val_str.parse() { self.$field = v; }
+                }
+            };
+        }
+
+        update!(setup_clue_min_hints);
+        update!(setup_clue_max_distance);
+        update!(discard_critical_penalty);
+        update!(discard_hinted_score);
+        update!(discard_useless_distance);
+        update!(endgame_deck_size);
+        update!(discard_endgame_penalty);
+        update!(clue_playable_weight);
+        update!(clue_useless_penalty);
+        update!(save_clue_min_hints);
+    }
+
+    // tries to load values from a file, falls back to default if file not found or parsing fails
+    pub fn load_from_file_or_default(filename: &str) -> Self {
+        let mut params = GeminiParams::default();
+        if let Ok(content) = fs::read_to_string(filename) {
+            for line in content.lines() {
+                let parts: Vec<&str> = line.split('=').map(|s| s.trim()).collect();
+                if parts.len() == 2 {
+                    params.set_from_str(parts[0], parts[1]);
+                }
+            }
+        }
+        params
+    }
+}
 
 /// The Gemini Strategy (v14 - "The Efficient Savior")
 /// 
@@ -10,7 +97,8 @@ use crate::decksubset::DeckSubset;
 /// - Prevents the "Redundant Hint Loop" seen in moves 1 vs 7.
 pub struct Gemini { 
     hints_remaining: u8,
-    fireworks: [u8; 5],
+    max_hints: u8, // the starting hint tokens, a discard never regains more
+    fireworks: [u8; 6],
     
     // Knowledge management
     my_hand_knowledge: Vec<DeckSubset>,
@@ -20,20 +108,40 @@ pub struct Gemini {
     // Board State tracking
     my_view_unknowns: DeckSubset, 
     public_unknowns: DeckSubset,
-    discarded_cards: Vec<Card>,
+    deck_remaining: usize,
+
+    number_of_players: usize,
+    params: GeminiParams,
+    conventions: bool, // the partner gives and reads hints through the shared conventions (see negotiate)
+}
+
+impl Default for Gemini {
+    fn default() -> Self {
+        Gemini::new()
+    }
 }
 
 impl Gemini {
+    // plays with gemini_params.txt, read once like Robert's params
     pub fn new() -> Self {
+        static DEFAULT_PARAMS: OnceLock<GeminiParams> = OnceLock::new();
+        Gemini::new_with_params(*DEFAULT_PARAMS.get_or_init(|| GeminiParams::load_from_file_or_default(&resolve_config_path("gemini_params.txt"))))
+    }
+
+    pub fn new_with_params(params: GeminiParams) -> Self {
         Gemini {
             hints_remaining: 8,
-            fireworks: [0; 5],
+            max_hints: 8,
+            fireworks: [0; 6],
             my_hand_knowledge: Vec::new(),
             partner_hand: Vec::new(),
             partner_hand_knowledge: Vec::new(),
             my_view_unknowns: DeckSubset::new_full(),
             public_unknowns: DeckSubset::new_full(),
-            discarded_cards: Vec::new(),
+            deck_remaining: 40,
+            number_of_players: 2,
+            params,
+            conventions: true,
         }
     }
 
@@ -48,55 +156,84 @@ impl Gemini {
         self.my_view_unknowns.remove_card(card);
     }
 
+    // a play or discard of a player that is not our partner, or a hint that is for neither of us
+    fn update_after_third_party_move(&mut self, mv_result: &MoveResult) {
+        match mv_result {
+            MoveResult::Play { drawn, .. } | MoveResult::Discard { drawn, .. } => {
+                if let Some((card, _)) = apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    self.mark_board_change(&card);
+                }
+                if let Some(new_card) = drawn { self.mark_partner_hand(new_card); }
+            }
+            MoveResult::Hint { .. } => self.hints_remaining = self.hints_remaining.saturating_sub(1),
+        }
+    }
+
+    // a partner that plays the conventions would misplay after a hint that isn't truthful,
+    // any other partner reads it for the cards it touches and can't be misled
+    fn is_safe_hint(&self, hint: &Move) -> bool {
+        !self.conventions || is_truthful(hint, &self.partner_hand_knowledge, &self.partner_hand, &self.fireworks)
+    }
+
     fn is_playable(&self, card: &Card) -> bool {
-        let color_idx = card.get_color() as usize;
+        let color_idx = card.get_color().index();
         let val = card.get_value();
         self.fireworks[color_idx] + 1 == val
     }
 
-    fn count_in_discard(&self, color: Color, value: u8) -> usize {
-        self.discarded_cards.iter()
+    fn count_in_discard(&self, color: Color, value: u8, discard_pile: &[Card]) -> usize {
+        discard_pile.iter()
             .filter(|&c| c.get_color() == color && c.get_value() == value)
             .count()
     }
 
-    fn is_useless(&self, card: &Card) -> bool {
-        let color_idx = card.get_color() as usize;
+    fn is_useless(&self, card: &Card, discard_pile: &[Card]) -> bool {
+        let color_idx = card.get_color().index();
         let val = card.get_value();
         let current_stack = self.fireworks[color_idx];
         if current_stack >= val { return true; }
         for req_val in (current_stack + 1)..val {
-            let copies_discarded = self.count_in_discard(card.get_color(), req_val);
-            let max_copies = match req_val { 1 => 3, 2 | 3 | 4 => 2, 5 => 1, _ => 1 };
+            let copies_discarded = self.count_in_discard(card.get_color(), req_val, discard_pile);
+            let max_copies = Card::from_color_value(card.get_color(), req_val).max_copies() as usize;
             if copies_discarded >= max_copies { return true; }
         }
         false
     }
 
-    fn get_distance(&self, card: &Card) -> u8 {
-        if self.is_useless(card) { return 255; }
-        let color_idx = card.get_color() as usize;
+    fn get_distance(&self, card: &Card, discard_pile: &[Card]) -> u8 {
+        if self.is_useless(card, discard_pile) { return 255; }
+        let color_idx = card.get_color().index();
         let val = card.get_value();
         let current_stack = self.fireworks[color_idx];
         if val <= current_stack { return 255; } 
         val - (current_stack + 1)
     }
 
-    fn is_card_critical(&self, card: &Card) -> bool {
-        if self.is_useless(card) { return false; }
+    fn is_card_critical(&self, card: &Card, discard_pile: &[Card]) -> bool {
+        if self.is_useless(card, discard_pile) { return false; }
         let val = card.get_value();
         if val == 5 { return true; } 
-        let copies_in_discard = self.count_in_discard(card.get_color(), val);
-        let max_copies = match val { 1 => 3, 2 | 3 | 4 => 2, _ => 1 };
+        let copies_in_discard = self.count_in_discard(card.get_color(), val, discard_pile);
+        let max_copies = card.max_copies() as usize;
         copies_in_discard + 1 >= max_copies
     }
 
+    fn is_endgame(&self) -> bool {
+        self.deck_remaining <= self.params.endgame_deck_size
+    }
+
+    // a card that is playable now or right after the next play. In the endgame there are not
+    // enough draws left to find another copy, so throwing one of these away usually costs a point
+    fn is_needed_soon(&self, card: &Card, discard_pile: &[Card]) -> bool {
+        !self.is_useless(card, discard_pile) && self.get_distance(card, discard_pile) <= 1
+    }
+
     // --- Knowledge Logic ---
 
     fn is_slot_certainly_playable(&self, index: usize) -> bool {
         if index >= self.my_hand_knowledge.len() { return false; }
         let possibilities = self.my_hand_knowledge[index].intersect(&self.my_view_unknowns);
-        if possibilities.0 == 0 { return false; }
+        if possibilities.is_empty() { return false; }
         for i in 0..50 {
             let c = &Card::new(i);
             if possibilities.has_card(c) {
@@ -106,14 +243,14 @@ impl Gemini {
         true
     }
 
-    fn is_slot_certainly_useless(&self, index: usize) -> bool {
+    fn is_slot_certainly_useless(&self, index: usize, discard_pile: &[Card]) -> bool {
         if index >= self.my_hand_knowledge.len() { return false; }
         let possibilities = self.my_hand_knowledge[index].intersect(&self.my_view_unknowns);
-        if possibilities.0 == 0 { return false; }
+        if possibilities.is_empty() { return false; }
         for i in 0..50 {
             let c = &Card::new(i);
             if possibilities.has_card(c) {
-                if !self.is_useless(c) { return false; }
+                if !self.is_useless(c, discard_pile) { return false; }
             }
         }
         true
@@ -126,7 +263,7 @@ impl Gemini {
 
     fn knowledge_implies_playable(&self, knowledge: &DeckSubset) -> bool {
         let possibilities = knowledge.intersect(&self.public_unknowns);
-        if possibilities.0 == 0 { return false; }
+        if possibilities.is_empty() { return false; }
         let mut possible_count = 0;
         for i in 0..50 {
             let c = &Card::new(i);
@@ -140,43 +277,52 @@ impl Gemini {
 
     /// Returns true if the partner's current knowledge confirms the card is critical.
     /// This prevents us from hinting "5" twice.
-    fn knowledge_implies_critical(&self, knowledge: &DeckSubset) -> bool {
+    fn knowledge_implies_critical(&self, knowledge: &DeckSubset, discard_pile: &[Card]) -> bool {
         let possibilities = knowledge.intersect(&self.public_unknowns);
-        if possibilities.0 == 0 { return false; }
+        if possibilities.is_empty() { return false; }
         for i in 0..50 {
             let c = &Card::new(i);
             if possibilities.has_card(c) {
-                if !self.is_card_critical(c) { return false; }
+                if !self.is_card_critical(c, discard_pile) { return false; }
             }
         }
         true
     }
 
-    fn calculate_discard_score(&self, index: usize) -> i32 {
+    fn calculate_discard_score(&self, index: usize, discard_pile: &[Card]) -> i32 {
         if index >= self.my_hand_knowledge.len() { return -9999; }
-        if self.is_slot_certainly_useless(index) { return 1000; }
+        if self.is_slot_certainly_useless(index, discard_pile) { return 1000; }
+        // a card we hold twice, or the partner holds too, is safe to let go
+        let resolved = resolved_hand(&self.my_hand_knowledge, &self.my_view_unknowns);
+        if is_known_duplicate(&resolved, index, &self.partner_hand) { return 900; }
 
         let possibilities = self.my_hand_knowledge[index].intersect(&self.my_view_unknowns);
         let mut total_count = 0;
         let mut critical_count = 0;
+        let mut needed_count = 0;
         let mut dist_accum = 0;
 
         for i in 0..50 {
             let c = &Card::new(i);
             if possibilities.has_card(c) {
                 total_count += 1;
-                if self.is_card_critical(&c) { critical_count += 1; }
-                let d = self.get_distance(&c);
-                if d == 255 { dist_accum += 20; } else { dist_accum += d as usize; }
+                if self.is_card_critical(c, discard_pile) { critical_count += 1; }
+                if self.is_needed_soon(c, discard_pile) { needed_count += 1; }
+                let d = self.get_distance(c, discard_pile);
+                if d == 255 { dist_accum += self.params.discard_useless_distance; } else { dist_accum += d as usize; }
             }
         }
 
         if total_count == 0 { return 0; }
-        if self.is_slot_hinted(index) { return -1000; }
+        if self.is_slot_hinted(index) { return self.params.discard_hinted_score; }
 
         let mut score = 100;
         let critical_prob = critical_count as f32 / total_count as f32;
-        score -= (critical_prob * 5000.0) as i32;
+        score -= (critical_prob * self.params.discard_critical_penalty) as i32;
+        if self.is_endgame() {
+            let needed_prob = needed_count as f32 / total_count as f32;
+            score -= (needed_prob * self.params.discard_endgame_penalty) as i32;
+        }
         score += dist_accum as i32 / total_count as i32;
         
         score
@@ -184,78 +330,81 @@ impl Gemini {
 }
 
 impl Strategy for Gemini {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>) {
-        self.hints_remaining = 8;
-        self.fireworks = [0; 5];
+    fn name(&self) -> &'static str { "Gemini" }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        let hand_size = other_player_hands[0].len();
+        self.number_of_players = other_player_hands.len() + 1;
+        self.hints_remaining = config.starting_hints;
+        self.max_hints = config.starting_hints;
+        self.fireworks = [0; 6];
         self.my_view_unknowns = DeckSubset::new_full();
         self.public_unknowns = DeckSubset::new_full();
-        self.discarded_cards.clear();
-        self.my_hand_knowledge = vec![DeckSubset::new_full(); 5];
-        self.partner_hand = other_player_hand.clone();
-        self.partner_hand_knowledge = vec![DeckSubset::new_full(); 5];
+        self.my_hand_knowledge = vec![DeckSubset::new_full(); hand_size];
+        self.partner_hand = other_player_hands[0].clone();
+        self.partner_hand_knowledge = vec![DeckSubset::new_full(); hand_size];
 
-        for card in other_player_hand {
+        for card in other_player_hands.iter().flatten() {
             self.mark_partner_hand(card);
         }
     }
 
-    fn decide_move(&mut self) -> Move {
+    fn reset(&mut self) {
+        *self = Gemini::new_with_params(self.params);
+    }
+
+    // hints are only read through the conventions if the partner gives them that way too
+    fn negotiate(&mut self, partner_name: &str) {
+        self.conventions = plays_conventions(partner_name);
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+        self.hints_remaining = view.hints_remaining;
+        self.fireworks = view.fireworks;
+        let discard_pile = view.discard_pile;
+        self.deck_remaining = view.deck_remaining;
         // --- 1. PLAY ---
+        // cards we know exactly go first, then slots where every possibility is playable
+        let resolved = resolved_hand(&self.my_hand_knowledge, &self.my_view_unknowns);
+        for i in (0..resolved.len()).rev() {
+            if let Some(card) = resolved[i] && self.is_playable(&card) { return Move::Play(i); }
+        }
         for i in (0..self.my_hand_knowledge.len()).rev() {
             if self.is_slot_certainly_playable(i) { return Move::Play(i); }
         }
 
         // --- 2. CHOP & SAVE ---
-        let mut partner_discard_idx = 0;
-        let mut found_chop = false;
-        for i in 0..self.partner_hand.len() {
-            if self.partner_hand_knowledge[i].0 == DeckSubset::new_full().0 {
-                partner_discard_idx = i;
-                found_chop = true;
-                break; 
-            }
-        }
-        if !found_chop { partner_discard_idx = 0; } 
-
-        if self.hints_remaining > 0 && !self.partner_hand.is_empty() {
+        // with every slot hinted the partner has no chop and nothing needs saving
+        if self.hints_remaining > self.params.save_clue_min_hints && let Some(partner_discard_idx) = chop_index(&self.partner_hand_knowledge) {
             let card_at_risk = self.partner_hand[partner_discard_idx];
             
             // SMART SAVE FIX:
             // Only hint if they don't already know it's critical.
             let knowledge = self.partner_hand_knowledge[partner_discard_idx];
-            let already_protected = self.knowledge_implies_critical(&knowledge);
+            let already_protected = self.knowledge_implies_critical(&knowledge, discard_pile);
 
-            if self.is_card_critical(&card_at_risk) && !already_protected {
+            if self.is_card_critical(&card_at_risk, discard_pile) && !already_protected {
                 return Move::HintValue(card_at_risk.get_value());
             }
         }
 
         // --- 3. PLAY CLUE (Pure Search) ---
         if self.hints_remaining > 0 {
-            struct ClueCandidate {
-                mv: Move,
-                playable_revealed: usize,
-                useless_touched: usize,
-            }
-            let mut candidates: Vec<ClueCandidate> = Vec::new();
-
-            let colors = [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White];
-            let values = [1, 2, 3, 4, 5];
+            // the best clue so far with its score, a later clue has to score strictly higher
+            let mut best_clue: Option<(Move, f64)> = None;
 
-            let mut analyze_hint = |mv: Move, indices: Vec<usize>| {
-                if indices.is_empty() { return; }
-                
+            for (mv, indices) in legal_hints(&self.partner_hand) {
+                // a hint the partner would read as a play of an unplayable card is never given
+                if !self.is_safe_hint(&mv) { continue; }
+                let knowledge = read_hint(self.conventions, &mv, &self.partner_hand_knowledge, &indices, &self.fireworks);
                 let mut playable_count = 0;
                 let mut useless_count = 0;
 
                 for &idx in &indices {
                     let card = &self.partner_hand[idx];
                     let old_k = self.partner_hand_knowledge[idx];
-                    let new_k = match mv {
-                        Move::HintColor(c) => old_k.intersect(&DeckSubset::from_color(c)),
-                        Move::HintValue(v) => old_k.intersect(&DeckSubset::from_value(v)),
-                        _ => old_k,
-                    };
+                    let new_k = knowledge[idx];
 
                     let was_known = self.knowledge_implies_playable(&old_k);
                     let will_be_known = self.knowledge_implies_playable(&new_k);
@@ -264,121 +413,94 @@ impl Strategy for Gemini {
                     if is_actually_playable && !was_known && will_be_known {
                         playable_count += 1;
                     }
-                    if self.is_useless(card) {
+                    if self.is_useless(card, discard_pile) {
                         useless_count += 1;
                     }
                 }
 
                 if playable_count > 0 {
-                    candidates.push(ClueCandidate { mv, playable_revealed: playable_count, useless_touched: useless_count });
+                    let score = playable_count as f64 * self.params.clue_playable_weight
+                        - useless_count as f64 * self.params.clue_useless_penalty;
+                    if best_clue.is_none_or(|(_, best)| score > best) {
+                        best_clue = Some((mv, score));
+                    }
                 }
-            };
-
-            for color in colors {
-                let indices: Vec<usize> = self.partner_hand.iter().enumerate()
-                    .filter(|(_, c)| c.get_color() == color).map(|(i, _)| i).collect();
-                analyze_hint(Move::HintColor(color), indices);
-            }
-            for val in values {
-                let indices: Vec<usize> = self.partner_hand.iter().enumerate()
-                    .filter(|(_, c)| c.get_value() == val).map(|(i, _)| i).collect();
-                analyze_hint(Move::HintValue(val), indices);
             }
 
-            if !candidates.is_empty() {
-                candidates.sort_by(|a, b| {
-                     let res = b.playable_revealed.cmp(&a.playable_revealed);
-                     if res != std::cmp::Ordering::Equal { return res; }
-                     a.useless_touched.cmp(&b.useless_touched)
-                });
-                return candidates[0].mv;
+            if let Some((mv, _)) = best_clue {
+                return mv;
             }
         }
 
         // --- 4. SETUP CLUE ---
-        if self.hints_remaining > 1 {
+        if self.hints_remaining > self.params.setup_clue_min_hints {
              for (i, card) in self.partner_hand.iter().enumerate() {
                  if self.partner_hand_knowledge[i].0 == DeckSubset::new_full().0 { 
-                     if self.is_useless(card) { continue; }
-                     let dist = self.get_distance(card);
-                     // Strict distance 1 check (no 5s allowed unless dist 1)
-                     if dist <= 1 {
-                         return Move::HintValue(card.get_value());
+                     if self.is_useless(card, discard_pile) { continue; }
+                     let dist = self.get_distance(card, discard_pile);
+                     // Strict distance check (no 5s allowed unless close enough)
+                     let hint = Move::HintValue(card.get_value());
+                     if dist <= self.params.setup_clue_max_distance && self.is_safe_hint(&hint) {
+                         return hint;
                      }
                  }
              }
         }
 
         // --- 5. DISCARD ---
-        if self.hints_remaining < 8 {
+        if self.hints_remaining < self.max_hints {
             let mut best_discard_idx = 0;
             let mut max_score = i32::MIN;
             for i in 0..self.my_hand_knowledge.len() {
-                let score = self.calculate_discard_score(i);
+                let score = self.calculate_discard_score(i, discard_pile);
                 if score > max_score {
                     max_score = score;
                     best_discard_idx = i;
                 }
             }
-            return Move::Discard(best_discard_idx);
+            // in the endgame a hint to stall is better than risking a card we still need
+            let risky = self.is_endgame() && max_score < 0;
+            if !(risky && self.hints_remaining > 0) {
+                return Move::Discard(best_discard_idx);
+            }
         }
 
         // --- 6. FORCE HINT ---
+        // any hint the partner won't misread, the value of the newest card if there is none
         if !self.partner_hand.is_empty() {
+             if let Some((hint, _)) = legal_hints(&self.partner_hand).into_iter()
+                 .find(|(hint, _)| self.is_safe_hint(hint)) {
+                 return hint;
+             }
              let last_idx = self.partner_hand.len() - 1;
              return Move::HintValue(self.partner_hand[last_idx].get_value());
         }
         
-        Move::Discard(0) 
+        safe_fallback_move(self.my_hand_knowledge.len(), &self.partner_hand, self.hints_remaining, view.discard_allowed)
     }
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
         match mv {
             Move::Play(idx) | Move::Discard(idx) => {
-                if *idx < self.my_hand_knowledge.len() { self.my_hand_knowledge.remove(*idx); }
-                if got_new_card { self.my_hand_knowledge.push(DeckSubset::new_full()); }
-                match mv_result {
-                    MoveResult::Play(success, card, _) => { 
-                        self.mark_board_change(card);
-                        if *success { self.fireworks[card.get_color() as usize] += 1; } 
-                        else { self.discarded_cards.push(*card); }
-                    },
-                    MoveResult::Discard(card, _) => {
-                        self.mark_board_change(card);
-                        self.discarded_cards.push(*card);
-                        if self.hints_remaining < 8 { self.hints_remaining += 1; }
-                    },
-                    _ => {}
-                }
-            },
-            Move::HintColor(c) => {
-                self.hints_remaining -= 1;
-                let mut hinted_indices = Vec::new();
-                for (i, card) in self.partner_hand.iter().enumerate() { if card.get_color() == *c { hinted_indices.push(i); } }
-                for i in 0..self.partner_hand_knowledge.len() {
-                    if hinted_indices.contains(&i) {
-                        self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color(*c));
-                    } else {
-                        self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color_inverted(*c));
-                    }
+                if let Some((card, _)) = apply_own_card_move(&mut self.my_hand_knowledge, *idx, got_new_card.then(DeckSubset::new_full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    self.mark_board_change(&card);
                 }
             },
-            Move::HintValue(v) => {
-                self.hints_remaining -= 1;
-                let mut hinted_indices = Vec::new();
-                for (i, card) in self.partner_hand.iter().enumerate() { if card.get_value() == *v { hinted_indices.push(i); } }
-                for i in 0..self.partner_hand_knowledge.len() {
-                    if hinted_indices.contains(&i) {
-                        self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value(*v));
-                    } else {
-                        self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value_inverted(*v));
-                    }
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
+                // the partner reads the hint the way we agreed on, so its knowledge is updated the same way
+                if let MoveResult::Hint { indices } = mv_result {
+                    self.partner_hand_knowledge = read_hint(self.conventions, mv, &self.partner_hand_knowledge, indices, &self.fireworks);
                 }
             }
         }
     }
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult) {
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        if !concerns_partner_or_me(player_offset, self.number_of_players, mv) {
+            self.update_after_third_party_move(mv_result);
+            return;
+        }
         let drawn_card_opt = match mv {
             Move::Play(idx) | Move::Discard(idx) => {
                 if *idx < self.partner_hand.len() {
@@ -387,14 +509,12 @@ impl Strategy for Gemini {
                     self.mark_board_change(&card);
 
                     match mv_result {
-                        MoveResult::Play(success, _, drawn) => {
-                            if *success { self.fireworks[card.get_color() as usize] += 1; } 
-                            else { self.discarded_cards.push(card); }
+                        MoveResult::Play { success, drawn, .. } => {
+                            if *success { self.fireworks[card.get_color().index()] += 1; }
                             drawn
                         },
-                        MoveResult::Discard(_, drawn) => {
-                            self.discarded_cards.push(card);
-                            if self.hints_remaining < 8 { self.hints_remaining += 1; }
+                        MoveResult::Discard { drawn, .. } => {
+                            if self.hints_remaining < self.max_hints { self.hints_remaining += 1; }
                             drawn
                         },
                         _ => &None 
@@ -403,29 +523,10 @@ impl Strategy for Gemini {
                     &None
                 }
             },
-            Move::HintColor(c) => {
-                self.hints_remaining -= 1;
-                let mut hinted_indices = Vec::new();
-                if let MoveResult::Hint(indices) = mv_result { hinted_indices = indices.clone(); }
-                for (i, subset) in self.my_hand_knowledge.iter_mut().enumerate() {
-                    if hinted_indices.contains(&i) {
-                        *subset = subset.intersect(&DeckSubset::from_color(*c));
-                    } else {
-                        *subset = subset.intersect(&DeckSubset::from_color_inverted(*c));
-                    }
-                }
-                &None
-            },
-            Move::HintValue(v) => {
-                self.hints_remaining -= 1;
-                let mut hinted_indices = Vec::new();
-                if let MoveResult::Hint(indices) = mv_result { hinted_indices = indices.clone(); }
-                for (i, subset) in self.my_hand_knowledge.iter_mut().enumerate() {
-                    if hinted_indices.contains(&i) {
-                        *subset = subset.intersect(&DeckSubset::from_value(*v));
-                    } else {
-                        *subset = subset.intersect(&DeckSubset::from_value_inverted(*v));
-                    }
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
+                if let MoveResult::Hint { indices } = mv_result {
+                    self.my_hand_knowledge = read_hint(self.conventions, mv, &self.my_hand_knowledge, indices, &self.fireworks);
                 }
                 &None
             }
@@ -437,4 +538,38 @@ impl Strategy for Gemini {
             self.partner_hand_knowledge.push(DeckSubset::new_full());
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::player::Player;
+
+    // the partner's chop holds the blue 5. With the default threshold it is saved, once saves need
+    // more hints than there are Gemini has to do something else
+    #[test]
+    fn save_clue_threshold_changes_the_decision() {
+        let decide = |params: GeminiParams| {
+            let players = vec![Player::new(Box::new(Gemini::new_with_params(params))), Player::new(Box::new(Gemini::new_with_params(params)))];
+            let hands = vec![(30..35).map(Card::new).collect(), vec![Card::new(29), Card::new(13), Card::new(15), Card::new(23), Card::new(43)]];
+            Game::new_with_hands(players, hands).decide_move()
+        };
+        assert_eq!(decide(GeminiParams::default()), Move::HintValue(5));
+        let reluctant = GeminiParams { save_clue_min_hints: 8, ..GeminiParams::default() };
+        assert_ne!(decide(reluctant), Move::HintValue(5));
+    }
+
+    // a known red 1 held twice is let go before any slot that is only probably safe
+    #[test]
+    fn known_duplicate_scores_as_a_safe_discard() {
+        let mut gemini = Gemini::new();
+        gemini.initialize(&[(10..15).map(Card::new).collect()], &GameConfig::for_players(2));
+        let red_one = DeckSubset::from_color(Color::Red).intersect(&DeckSubset::from_value(1));
+        gemini.my_hand_knowledge[1] = red_one;
+        gemini.my_hand_knowledge[3] = red_one;
+        assert_eq!(gemini.calculate_discard_score(1, &[]), 900);
+        assert_eq!(gemini.calculate_discard_score(3, &[]), 900);
+        assert!(gemini.calculate_discard_score(0, &[]) < 900);
+    }
+}
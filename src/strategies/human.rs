@@ -3,147 +3,197 @@ use crate::card::Card;
 use crate::strategy::Strategy;
 use std::io::{self, Write};
 use crate::decksubset::DeckSubset;
+use crate::strategy_utils::{apply_own_card_move, apply_card_result, concerns_partner_or_me, apply_hint};
+use crate::game::{GameConfig, GameView};
 
 
 
+const USAGE: &str = "Moves: play <slot>, discard <slot>, hint <color|value>, hint color <color>, hint value <value>, notes <slot>, help";
+
+// what the user typed, once it parsed
+enum Command {
+    Move(Move),
+    Notes(usize), // show every card a slot can still be
+    Help,
+}
+
 pub struct Human { 
     hints_remaining: u8,
-    fireworks: [u8; 5],
+    max_hints: u8, // the starting hint tokens, a discard never regains more
+    fireworks: [u8; 6],
     my_hand_knowledge: Vec<DeckSubset>,
     partner_hand: Vec<Card>,
     partner_hand_knowledge: Vec<DeckSubset>,
-    cards_not_seen: DeckSubset
+    cards_not_seen: DeckSubset,
+    number_of_players: usize,
+}
+
+impl Default for Human {
+    fn default() -> Self {
+        Human::new()
+    }
 }
 
 impl Human {
     pub fn new() -> Self {
         Human {
             hints_remaining: 8,
-            fireworks: [0; 5],
+            max_hints: 8,
+            fireworks: [0; 6],
             my_hand_knowledge: vec![DeckSubset::new_full(); 5],
             partner_hand: Vec::new(),
             partner_hand_knowledge: vec![DeckSubset::new_full(); 5],
-            cards_not_seen: DeckSubset::new_full()
+            cards_not_seen: DeckSubset::new_full(),
+            number_of_players: 2,
+        }
+    }
+
+    // slots are counted from 1 like on screen
+    fn parse_command(input: &str) -> Result<Command, String> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let slot = |slot_str: &str| match slot_str.parse::<usize>() {
+            Ok(slot) if slot >= 1 => Ok(slot - 1),
+            _ => Err(format!("'{}' is not a slot", slot_str)),
+        };
+        let value = |value_str: &str| match value_str.parse::<u8>() {
+            Ok(value) if (1..=5).contains(&value) => Ok(Move::HintValue(value)),
+            _ => Err(format!("'{}' is not a value from 1 to 5", value_str)),
+        };
+        let color = |color_str: &str| match color_str {
+            "red" => Ok(Move::HintColor(Color::Red)),
+            "green" => Ok(Move::HintColor(Color::Green)),
+            "blue" => Ok(Move::HintColor(Color::Blue)),
+            "yellow" => Ok(Move::HintColor(Color::Yellow)),
+            "white" => Ok(Move::HintColor(Color::White)),
+            _ => Err(format!("'{}' is not a color", color_str)),
+        };
+        match parts.as_slice() {
+            ["help"] => Ok(Command::Help),
+            ["notes", slot_str] => slot(slot_str).map(Command::Notes),
+            ["play", slot_str] => slot(slot_str).map(|i| Command::Move(Move::Play(i))),
+            ["discard", slot_str] => slot(slot_str).map(|i| Command::Move(Move::Discard(i))),
+            // the long forms name the kind of hint, the short one tells colors and values apart by itself
+            ["hint", "value", value_str] => value(value_str).map(Command::Move),
+            ["hint", "color", color_str] => color(color_str).map(Command::Move),
+            ["hint", hint_str] if hint_str.parse::<u8>().is_ok() => value(hint_str).map(Command::Move),
+            ["hint", hint_str] => color(hint_str).map(Command::Move),
+            [] => Err("Nothing entered".to_string()),
+            _ => Err(format!("'{}' is not a move", input.trim())),
+        }
+    }
+
+    // what a slot of our hand can still be, from the hints we got and the cards we saw
+    fn possible_cards(&self, idx: usize) -> DeckSubset {
+        self.my_hand_knowledge[idx].intersect(&self.cards_not_seen)
+    }
+
+    // the pencil marks of our hand: per slot what the hints tell and the card types it can still be
+    fn print_notes(&self) {
+        for i in 0..self.my_hand_knowledge.len() {
+            let possible = self.possible_cards(i);
+            let mut types: Vec<String> = Vec::new();
+            for card in possible.iter_cards() {
+                let name = card.to_code();
+                if !types.contains(&name) { types.push(name); }
+            }
+            println!("Slot {}: {} possibly [{}]", i + 1, possible.summary(), types.join(" "));
+        }
+    }
+
+    // a play or discard of a player that is not our partner, or a hint that is for neither of us
+    fn update_after_third_party_move(&mut self, mv_result: &MoveResult) {
+        match mv_result {
+            MoveResult::Play { card, drawn, .. } | MoveResult::Discard { card, drawn } => {
+                apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints);
+                self.cards_not_seen.remove_card(card);
+                if let Some(new_card) = drawn { self.cards_not_seen.remove_card(new_card); }
+            }
+            MoveResult::Hint { .. } => self.hints_remaining = self.hints_remaining.saturating_sub(1),
         }
     }
 }
 
 impl Strategy for Human {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>) {
-        self.partner_hand = other_player_hand.clone();
-        for card in other_player_hand {
+    fn name(&self) -> &'static str { "Human" }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.hints_remaining = config.starting_hints;
+        self.max_hints = config.starting_hints;
+        self.number_of_players = other_player_hands.len() + 1;
+        self.partner_hand = other_player_hands[0].clone();
+        self.my_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        self.partner_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        for card in other_player_hands.iter().flatten() {
             self.cards_not_seen.remove_card(card);
         }
     }
 
-    fn decide_move(&mut self) -> Move {
-        // just ask the user for input
-        print!("Enter your move: ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
-        match parts.as_slice() {
-            ["play", index_str] => {
-                let index: usize = index_str.parse::<usize>().unwrap()-1;
-                Move::Play(index)
-            },
-            ["discard", index_str] => {
-                let index: usize = index_str.parse::<usize>().unwrap()-1;
-                Move::Discard(index)
-            },
-            ["hint", hint_str] => {
-                match *hint_str {
-                    "red" => Move::HintColor(Color::Red),
-                    "green" => Move::HintColor(Color::Green),
-                    "blue" => Move::HintColor(Color::Blue),
-                    "yellow" => Move::HintColor(Color::Yellow),
-                    "white" => Move::HintColor(Color::White),
-                    "1" => Move::HintValue(1),
-                    "2" => Move::HintValue(2),
-                    "3" => Move::HintValue(3),
-                    "4" => Move::HintValue(4),
-                    "5" => Move::HintValue(5),
-                    _ => panic!("Invalid hint"),
-                }
-            },
-            _ => panic!("Invalid move format"),
+    fn reset(&mut self) {
+        *self = Human::new();
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+        self.hints_remaining = view.hints_remaining;
+        self.fireworks = view.fireworks;
+        self.print_notes();
+        // ask the user until the input is a move, the game itself checks if the move is allowed
+        loop {
+            print!("Enter your move: ");
+            io::stdout().flush().unwrap();
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                println!("\nInput closed, quitting.");
+                std::process::exit(0);
+            }
+            match Self::parse_command(&input) {
+                Ok(Command::Move(mv)) => return mv,
+                Ok(Command::Notes(i)) if i < self.my_hand_knowledge.len() => print!("{}", self.possible_cards(i)),
+                Ok(Command::Notes(_)) => println!("There is no such slot."),
+                Ok(Command::Help) => println!("{}", USAGE),
+                Err(reason) => println!("{}. {}", reason, USAGE),
+            }
         }
     }
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
         match mv {
-            Move::Play(idx) => {
-                match mv_result {
-                    MoveResult::Play(success, card_played, _) => {
-                        if *success {
-                            // Update fireworks
-                            let color_index = card_played.get_color() as usize;
-                            self.fireworks[color_index] += 1;
-                        } 
-                        // Remove played card knowledge
-                        self.my_hand_knowledge.remove(*idx);
-                        if got_new_card {
-                            self.my_hand_knowledge.push(DeckSubset::new_full());
-                        }
-                    },
-                    _ => ()
-                }
-            }
-            Move::Discard(idx) => {
-                // Remove discarded card knowledge
-                self.my_hand_knowledge.remove(*idx);
-                if got_new_card {
-                    self.my_hand_knowledge.push(DeckSubset::new_full());
-                }
-                if self.hints_remaining < 8 {
-                    self.hints_remaining += 1;
+            Move::Play(idx) | Move::Discard(idx) => {
+                if let Some((card, _)) = apply_own_card_move(&mut self.my_hand_knowledge, *idx, got_new_card.then(DeckSubset::new_full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    self.cards_not_seen.remove_card(&card);
                 }
             }
-            Move::HintColor(color) => {
-                self.hints_remaining -= 1;
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update partner's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
-                        }
-                    },
-                    _ => ()
-                }
-            }
-            Move::HintValue(value) => {
-                self.hints_remaining -= 1;
-                // Update partner's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
-                        }
-                    },
-                    _ => ()
+                if let MoveResult::Hint { indices } = mv_result {
+                    apply_hint(&mut self.partner_hand_knowledge, indices, mv);
                 }
             }
         }
     }
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult) {
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        if !concerns_partner_or_me(player_offset, self.number_of_players, mv) {
+            self.update_after_third_party_move(mv_result);
+            return;
+        }
         match mv {
             Move::Play(idx) => {
                 match mv_result {
-                    MoveResult::Play(success, card_played, card_drawn) => {
+                    MoveResult::Play { success, card: card_played, drawn: card_drawn } => {
                         self.cards_not_seen.remove_card(card_played); // both see this card
                         if *success {
                             // Update fireworks
-                            let color_index = card_played.get_color() as usize;
+                            let color_index = card_played.get_color().index();
                             self.fireworks[color_index] += 1;
                         } 
                         // Remove played card knowledge and hand and add new card if drawn
-                        self.my_hand_knowledge.remove(*idx);
+                        self.partner_hand_knowledge.remove(*idx);
                         self.partner_hand.remove(*idx);
                         if let Some(card) = card_drawn {
                             self.partner_hand.push(*card);
-                            self.my_hand_knowledge.push(DeckSubset::new_full());
+                            self.partner_hand_knowledge.push(DeckSubset::new_full());
                             self.cards_not_seen.remove_card(card);
                         }
                     },
@@ -152,45 +202,28 @@ impl Strategy for Human {
             }
             Move::Discard(idx) => {
                 match mv_result {
-                    MoveResult::Discard(card_discarded, card_drawn) => {
+                    MoveResult::Discard { card: card_discarded, drawn: card_drawn } => {
                         self.cards_not_seen.remove_card(card_discarded); // both see this card
-                        if self.hints_remaining < 8 {
+                        if self.hints_remaining < self.max_hints {
                             self.hints_remaining += 1;
                         }
                         // Remove played card knowledge and hand and add new card if drawn
-                        self.my_hand_knowledge.remove(*idx);
+                        self.partner_hand_knowledge.remove(*idx);
                         self.partner_hand.remove(*idx);
                         if let Some(card) = card_drawn {
                             self.partner_hand.push(*card);
-                            self.my_hand_knowledge.push(DeckSubset::new_full());
+                            self.partner_hand_knowledge.push(DeckSubset::new_full());
                             self.cards_not_seen.remove_card(card);
                         }
                     },
                     _ => ()
                 }
             }
-            Move::HintColor(color) => {
-                self.hints_remaining -= 1;
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update own's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
-                        }
-                    },
-                    _ => ()
-                }
-            }
-            Move::HintValue(value) => {
-                self.hints_remaining -= 1;
-                // Update own's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
-                        }
-                    },
-                    _ => ()
+                if let MoveResult::Hint { indices } = mv_result {
+                    apply_hint(&mut self.my_hand_knowledge, indices, mv);
                 }
             }
         }
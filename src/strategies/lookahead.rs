@@ -0,0 +1,122 @@
+use crate::enums::{Move, MoveResult};
+use crate::card::Card;
+use crate::strategy::Strategy;
+use crate::conventions::playable_cards;
+use crate::strategy_utils::{wrapper_name, BoardTracker};
+use crate::game::{legal_moves, GameConfig, GameView};
+
+// what a misplay costs on top of the card it burns, in points of the fireworks. On the last strike it costs the whole score
+const MISTAKE_COST: f64 = 3.0;
+
+/// Wraps any strategy and checks each of its moves one move ahead.
+///
+/// Every legal move gets a value from its immediate effect on the fireworks: a play is worth the chance
+/// that the card is playable, minus the chance of a mistake times what the mistake costs. Hints and
+/// discards don't change the fireworks and are worth nothing. The inner strategy's move is kept unless
+/// another move is worth more, so it decides the ties and everything the fireworks don't show.
+/// All callbacks are forwarded to the inner strategy.
+pub struct OnePlyLookahead {
+    inner: Box<dyn Strategy>,
+    board: BoardTracker,
+}
+
+impl OnePlyLookahead {
+    pub fn new(inner: Box<dyn Strategy>) -> Self {
+        OnePlyLookahead { inner, board: BoardTracker::new() }
+    }
+
+    // the chance that the card in slot idx is playable. The inner strategy knows its own conventions best,
+    // so its estimate is taken if it has one, otherwise we count every copy we have not seen
+    fn chance_playable(&self, idx: usize) -> f64 {
+        if let Some(p) = self.inner.play_probability(idx) {
+            return p;
+        }
+        let possibilities = self.board.possibilities(idx);
+        if possibilities.is_empty() {
+            return 0.0;
+        }
+        possibilities.intersect(&playable_cards(&self.board.fireworks)).count() as f64 / possibilities.count() as f64
+    }
+
+    fn value(&self, mv: &Move) -> f64 {
+        let Move::Play(idx) = *mv else { return 0.0 };
+        if idx >= self.board.my_hand_knowledge.len() {
+            return 0.0;
+        }
+        let mistake_cost = if self.board.mistakes_made + 1 >= self.board.max_mistakes {
+            self.board.fireworks.iter().map(|&f| f as f64).sum::<f64>() + 1.0
+        } else {
+            MISTAKE_COST
+        };
+        let p = self.chance_playable(idx);
+        p - (1.0 - p) * mistake_cost
+    }
+}
+
+impl Strategy for OnePlyLookahead {
+    // the registered wrappers get their registry name, any other wrapped strategy the generic one
+    fn name(&self) -> &'static str {
+        wrapper_name(self.inner.name(), &[("Gemini", "LookaheadGemini"), ("Robert", "LookaheadRobert")], "OnePlyLookahead")
+    }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.board.initialize(other_player_hands, config);
+        self.inner.initialize(other_player_hands, config);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.board = BoardTracker::new();
+    }
+
+    fn negotiate(&mut self, partner_name: &str) {
+        self.board.negotiate(self.name(), partner_name);
+        self.inner.negotiate(partner_name);
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        self.board.update_from_view(view);
+        let mv = self.inner.decide_move(view);
+        // among the moves worth the same, a hint goes before a discard, it doesn't throw a card away
+        let best = legal_moves(view, &self.board.partner_hand).into_iter()
+            .map(|candidate| (candidate, self.value(&candidate)))
+            .max_by(|(a, value_a), (b, value_b)| value_a.total_cmp(value_b)
+                .then(matches!(a, Move::HintColor(_) | Move::HintValue(_)).cmp(&matches!(b, Move::HintColor(_) | Move::HintValue(_)))));
+        // the inner strategy may play on conventions the fireworks don't show, so its move is never worth less
+        // than a hint. Blocking risky plays is left to the SafetyNet, the lookahead only adds plays
+        match best {
+            Some((candidate, value)) if value > self.value(&mv).max(0.0) => candidate,
+            _ => mv,
+        }
+    }
+
+    fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
+        self.board.update_after_own_move(mv, mv_result, got_new_card);
+        self.inner.update_after_own_move(mv, mv_result, got_new_card);
+    }
+
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        self.board.update_after_other_player_move(player_offset, mv, mv_result);
+        self.inner.update_after_other_player_move(player_offset, mv, mv_result);
+    }
+
+    fn see(&mut self, card: &Card) {
+        self.inner.see(card);
+    }
+
+    fn seed_rng(&mut self, seed: u64) {
+        self.inner.seed_rng(seed);
+    }
+
+    fn on_game_over(&mut self, final_score: u8) {
+        self.inner.on_game_over(final_score);
+    }
+
+    fn play_probability(&self, idx: usize) -> Option<f64> {
+        self.inner.play_probability(idx)
+    }
+
+    fn game_log(&self) -> Option<String> {
+        self.inner.game_log()
+    }
+}
@@ -4,4 +4,104 @@ pub mod human;
 pub mod robert;
 
 pub mod cheater;
-// pub mod robert2;
\ No newline at end of file
+pub mod safety_net;
+pub mod robert2;
+pub mod observer;
+pub mod lookahead;
+
+use crate::StrategyFactory;
+
+// every strategy that can be picked by name, with a factory that builds a fresh one for each game
+pub fn registry() -> Vec<(&'static str, StrategyFactory<'static>)> {
+    vec![
+        ("Gemini", &|| Box::new(gemini::Gemini::new())),
+        ("ChatGPT", &|| Box::new(chatgpt::ChatGPT::new())),
+        ("Robert", &|| Box::new(robert::Robert::new())),
+        ("Human", &|| Box::new(human::Human::new())),
+        ("RobertDirect", &|| Box::new(robert::Robert::new().without_conventions())),
+        ("RobertFinesse", &|| Box::new(robert::Robert::new().with_finesse())),
+        ("Robert2", &|| Box::new(robert2::Robert2::new())),
+        // same strategies, but never risking the last strike
+        ("SafeGemini", &|| Box::new(safety_net::SafetyNet::new(Box::new(gemini::Gemini::new())))),
+        ("SafeChatGPT", &|| Box::new(safety_net::SafetyNet::new(Box::new(chatgpt::ChatGPT::new())))),
+        // same strategies, but playing instead whenever a play is worth more one move ahead.
+        // no stronger than the inner strategy: LookaheadRobert scores a little below Robert in self-play
+        ("LookaheadGemini", &|| Box::new(lookahead::OnePlyLookahead::new(Box::new(gemini::Gemini::new())))),
+        ("LookaheadRobert", &|| Box::new(lookahead::OnePlyLookahead::new(Box::new(robert::Robert::new())))),
+        // sees every card, including its own hand and the deck
+        ("Cheater", &|| Box::new(cheater::Cheater::new())),
+        // only stalls and keeps a log of everything it saw, printed after a single game
+        ("Observer", &|| Box::new(observer::Observer::new())),
+    ]
+}
+
+// strategies that can't be benchmarked because they wait for input
+pub const INTERACTIVE: [&str; 1] = ["Human"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::deck::Deck;
+    use crate::enums::{Color, Move, MoveResult};
+    use crate::game::{Game, GameConfig};
+    use crate::player::Player;
+
+    // deep in the endgame the partner may have played out their hand. every strategy must still find
+    // a move the engine accepts then, and none may hint a hand without cards
+    #[test]
+    fn every_strategy_moves_legally_against_an_empty_partner_hand() {
+        let my_hand: Vec<Card> = (0..5).map(Card::new).collect();
+        let partner_hand: Vec<Card> = (10..15).map(Card::new).collect();
+        for (name, factory) in registry().into_iter().filter(|(name, _)| !INTERACTIVE.contains(name)) {
+            let players = vec![Player::new(factory()), Player::new(factory())];
+            let mut game = Game::new_with_hands(players, vec![my_hand.clone(), partner_hand.clone()]);
+            // the partner discards their whole hand without drawing
+            game.players[1].hand.clear();
+            for card in &partner_hand {
+                game.players[0].strategy.update_after_other_player_move(1, &Move::Discard(0), &MoveResult::Discard { card: *card, drawn: None });
+            }
+            let mv = game.decide_move();
+            assert_eq!(game.check_move(&mv), Ok(()), "{} chose {:?}", name, mv);
+        }
+    }
+
+    // we hold a red 4 on the chop and the partner has thrown away the other one, red is at 2. the partner's
+    // hand holds nothing playable, hints are at 4. after the staged moves the deck holds the cards below
+    fn decision_with_red_four_on_chop(factory: StrategyFactory, below: &[Card]) -> Move {
+        let my_hand = [8, 43, 45, 33, 35];
+        let partner_hand = [0, 3, 7, 15, 25];
+        let draws = [47, 48, 37, 38, 17];
+        let mut deck = Deck { cards: below.to_vec() };
+        let dealt = my_hand.iter().zip(partner_hand.iter()).flat_map(|(&mine, &theirs)| [mine, theirs]);
+        let draw_order: Vec<u8> = dealt.chain(draws).collect();
+        for &code in draw_order.iter().rev() {
+            deck.push_next(Card::new(code));
+        }
+
+        let players = vec![Player::new(factory()), Player::new(factory())];
+        let mut game = Game::new_with_deck(players, GameConfig::for_players(2), deck);
+        game.apply_moves(&[
+            Move::HintColor(Color::Red), Move::Play(0), // red 1
+            Move::HintValue(3), Move::Play(0), // red 2
+            Move::HintValue(4), Move::Discard(0), // the partner's red 4
+            Move::HintColor(Color::Green), Move::Play(0), // green 3 misplayed
+            Move::HintColor(Color::Blue), Move::Play(0), // blue 3 misplayed
+        ]).expect("the staged moves are legal");
+        assert_eq!(game.deck.remaining(), below.len());
+        game.decide_move()
+    }
+
+    // with the deck nearly out the red 4 can't be replaced, so it is kept and a hint stalls instead
+    #[test]
+    fn last_needed_four_is_kept_when_the_deck_is_nearly_empty() {
+        let endgame: Vec<Card> = [29, 49].map(Card::new).to_vec();
+        let midgame: Vec<Card> = [20, 21, 22, 23, 24, 26, 27, 28, 29, 49].map(Card::new).to_vec();
+        for (name, factory) in registry().into_iter().filter(|(name, _)| ["Gemini", "ChatGPT"].contains(name)) {
+            let mv = decision_with_red_four_on_chop(factory, &midgame);
+            assert_eq!(mv, Move::Discard(0), "{} with 10 cards left", name);
+            let mv = decision_with_red_four_on_chop(factory, &endgame);
+            assert!(!matches!(mv, Move::Discard(_)), "{} chose {:?} with 2 cards left", name, mv);
+        }
+    }
+}
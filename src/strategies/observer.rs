@@ -0,0 +1,113 @@
+use crate::enums::{Move, MoveResult};
+use crate::card::Card;
+use crate::strategy::Strategy;
+use crate::strategy_utils::hint_touching;
+use crate::game::{GameConfig, GameView};
+
+/// A passive seat that writes down everything the game tells it.
+///
+/// It never tries to score: it stalls with a hint touching the first card of the next player,
+/// or discards its oldest card without hints. Every callback adds a readable line to the log,
+/// which is kept in memory and handed out by dump, so the game can be traced from a neutral seat.
+/// Other seats are named by how many seats after us they sit, like +1 for the next player.
+pub struct Observer {
+    log: Vec<String>,
+    other_hands: Vec<Vec<Card>>, // in turn order, starting with the next player
+}
+
+impl Default for Observer {
+    fn default() -> Self {
+        Observer::new()
+    }
+}
+
+impl Observer {
+    pub fn new() -> Self {
+        Observer { log: Vec::new(), other_hands: Vec::new() }
+    }
+
+    // everything seen so far, one line per event
+    pub fn dump(&self) -> String {
+        self.log.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    fn hand_codes(hand: &[Card]) -> String {
+        hand.iter().map(|card| card.to_code()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn describe(mv: &Move, mv_result: &MoveResult) -> String {
+        let drew = |drawn: &Option<Card>| match drawn {
+            Some(card) => format!(", drew {}", card.to_code()),
+            None => String::new(),
+        };
+        match (mv, mv_result) {
+            (Move::Play(idx), MoveResult::Play { success, card, drawn }) => {
+                format!("played slot {} {} ({}){}", idx + 1, card.to_code(), if *success { "success" } else { "mistake" }, drew(drawn))
+            }
+            (Move::Discard(idx), MoveResult::Discard { card, drawn }) => format!("discarded slot {} {}{}", idx + 1, card.to_code(), drew(drawn)),
+            (Move::HintColor(color), MoveResult::Hint { indices }) => format!("hinted {:?} touching slots {:?}", color, indices.iter().map(|i| i + 1).collect::<Vec<_>>()),
+            (Move::HintValue(value), MoveResult::Hint { indices }) => format!("hinted {}s touching slots {:?}", value, indices.iter().map(|i| i + 1).collect::<Vec<_>>()),
+            _ => format!("{:?} -> {:?}", mv, mv_result),
+        }
+    }
+}
+
+impl Strategy for Observer {
+    fn name(&self) -> &'static str { "Observer" }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.log.clear();
+        self.other_hands = other_player_hands.to_vec();
+        self.log.push(format!("rules: {} hints, {} mistakes, {} cards per hand, {:?}", config.starting_hints, config.max_mistakes, config.hand_size, config.variant));
+        for (i, hand) in self.other_hands.iter().enumerate() {
+            self.log.push(format!("seat +{} holds {}", i + 1, Self::hand_codes(hand)));
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Observer::new();
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        self.log.push(format!("my turn: fireworks {:?}, hints {}, mistakes {}, deck {}, discards {}",
+            view.fireworks, view.hints_remaining, view.mistakes_made, view.deck_remaining, Self::hand_codes(view.discard_pile)));
+        match self.other_hands.first().and_then(|hand| hand.first()) {
+            Some(card) if view.hints_remaining > 0 => hint_touching(card),
+            _ => Move::Discard(0),
+        }
+    }
+
+    fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
+        let drew = if got_new_card { ", drew an unseen card" } else { "" };
+        self.log.push(format!("I {}{}", Self::describe(mv, mv_result), drew));
+    }
+
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        let number_of_players = self.other_hands.len() + 1;
+        match mv {
+            Move::Play(idx) | Move::Discard(idx) => {
+                if let Some(hand) = self.other_hands.get_mut(player_offset - 1) && *idx < hand.len() {
+                    hand.remove(*idx);
+                    if let MoveResult::Play { drawn: Some(card), .. } | MoveResult::Discard { drawn: Some(card), .. } = mv_result {
+                        hand.push(*card);
+                    }
+                }
+                self.log.push(format!("seat +{} {}", player_offset, Self::describe(mv, mv_result)));
+            }
+            Move::HintColor(_) | Move::HintValue(_) => {
+                // a hint goes to the player after the one giving it
+                let receiver = (player_offset + 1) % number_of_players;
+                let to = if receiver == 0 { "me".to_string() } else { format!("seat +{}", receiver) };
+                self.log.push(format!("seat +{} {} for {}", player_offset, Self::describe(mv, mv_result), to));
+            }
+        }
+    }
+
+    fn on_game_over(&mut self, final_score: u8) {
+        self.log.push(format!("game over with {} points", final_score));
+    }
+
+    fn game_log(&self) -> Option<String> {
+        Some(self.dump())
+    }
+}
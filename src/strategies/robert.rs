@@ -1,8 +1,12 @@
-use crate::enums::{Move, MoveResult, Color};
+use crate::enums::{Move, MoveResult, Color, GameVariant};
 use crate::card::Card;
 use crate::strategy::Strategy;
 use crate::decksubset::DeckSubset;
+use crate::config::resolve_config_path;
+use crate::strategy_utils::{apply_own_card_move, apply_card_result, concerns_partner_or_me, is_touched, resolved_card, safe_fallback_move, knowledge_after_hint};
+use crate::game::{legal_moves, GameConfig, GameView};
 use std::fs;
+use std::sync::OnceLock;
 use std::str::FromStr;
 
 // robert.rs
@@ -25,6 +29,7 @@ pub struct Params {
     pub score_play_make_discardable_weighted_by_partner_knowledge: f64,
     pub score_play_sure: f64,
     pub score_play_focused_hint: f64,
+    pub score_play_finesse: f64,
 
     // DISCARDING
     pub score_discard_exponent_probability: i32,
@@ -62,6 +67,7 @@ impl Default for Params {
             score_play_make_discardable_weighted_by_partner_knowledge: 2.0,
             score_play_sure: 100.0,
             score_play_focused_hint: 100.0,
+            score_play_finesse: 5000.0,
 
             // DISCARDING
             score_discard_exponent_probability: 2,
@@ -84,6 +90,103 @@ impl Default for Params {
 }
 
 impl Params {
+    // sets the field called key to the parsed value, unknown keys or unparsable values are ignored
+    fn set_from_str(&mut self, key: &str, val_str: &str) {
+        // Helper macro to update fields to avoid repetition
+        macro_rules! update_f64 {
+            ($field:ident) => {
+                if key == stringify!($field) {
+                    if let Ok(v) = f64::from_str(val_str) { self.$field = v; }
+                }
+            };
+        }
+        macro_rules! update_i32 {
+            ($field:ident) => {
+                if key == stringify!($field) {
+                    if let Ok(v) = i32::from_str(val_str) { self.$field = v; }
+                }
+            };
+        }
+
+        update_f64!(score_play_base);
+        update_f64!(score_discard_base);
+        update_f64!(score_hint_base);
+
+        update_i32!(score_play_exponent_probability);
+        update_f64!(score_play_by_playability_weight);
+        update_f64!(score_play_badness_mistake_weight);
+        update_f64!(score_play_can_play_5_sure);
+        update_f64!(score_play_make_playable);
+        update_f64!(score_play_make_playable_weighted_by_partner_knowledge);
+        update_f64!(score_play_make_discardable);
+        update_f64!(score_play_make_discardable_weighted_by_partner_knowledge);
+        update_f64!(score_play_sure);
+        update_f64!(score_play_focused_hint);
+        update_f64!(score_play_finesse);
+
+        update_i32!(score_discard_exponent_probability);
+        update_f64!(score_discard_value_of_a_hint);
+        update_f64!(score_discard_probability_weight);
+        update_f64!(score_discard_badness_mistake_weight);
+        update_f64!(score_discard_hints_low_weight);
+
+        update_f64!(score_hint_focused_hint);
+        update_i32!(score_hint_exponent_information_gain);
+        update_f64!(score_hint_information_gain);
+        update_f64!(score_hint_make_playable);
+        update_f64!(score_hint_make_discardable);
+
+        update_f64!(score_badness_discard_only_card_left_of_its_kind);
+    }
+
+    // every field as a key = value line, the format load_from_file_or_default reads back
+    pub fn file_content(&self) -> String {
+        let mut content = String::new();
+
+        macro_rules! write_line {
+            ($field:ident) => {
+                content.push_str(&format!("{} = {}\n", stringify!($field), self.$field));
+            };
+        }
+
+        write_line!(score_play_base);
+        write_line!(score_discard_base);
+        write_line!(score_hint_base);
+
+        write_line!(score_play_exponent_probability);
+        write_line!(score_play_by_playability_weight);
+        write_line!(score_play_badness_mistake_weight);
+        write_line!(score_play_can_play_5_sure);
+        write_line!(score_play_make_playable);
+        write_line!(score_play_make_playable_weighted_by_partner_knowledge);
+        write_line!(score_play_make_discardable);
+        write_line!(score_play_make_discardable_weighted_by_partner_knowledge);
+        write_line!(score_play_sure);
+        write_line!(score_play_focused_hint);
+        write_line!(score_play_finesse);
+
+        write_line!(score_discard_exponent_probability);
+        write_line!(score_discard_value_of_a_hint);
+        write_line!(score_discard_probability_weight);
+        write_line!(score_discard_badness_mistake_weight);
+        write_line!(score_discard_hints_low_weight);
+
+        write_line!(score_hint_focused_hint);
+        write_line!(score_hint_exponent_information_gain);
+        write_line!(score_hint_information_gain);
+        write_line!(score_hint_make_playable);
+        write_line!(score_hint_make_discardable);
+
+        write_line!(score_badness_discard_only_card_left_of_its_kind);
+
+        content
+    }
+
+    // writes the defaults of every param, for a starting point to tune from
+    pub fn write_default_template(path: &str) -> std::io::Result<()> {
+        fs::write(path, format!("# Robert params, every key with its default value\n{}", Params::default().file_content()))
+    }
+
     // tries to load values from a file, falls back to default if file not found or parsing fails
     pub fn load_from_file_or_default(filename: &str) -> Self {
         let mut params = Params::default();
@@ -93,53 +196,7 @@ impl Params {
             for line in content.lines() {
                 let parts: Vec<&str> = line.split('=').map(|s| s.trim()).collect();
                 if parts.len() == 2 {
-                    let key = parts[0];
-                    let val_str = parts[1];
-                    
-                    // Helper macro to update fields to avoid repetition
-                    macro_rules! update_f64 {
-                        ($field:ident) => {
-                            if key == stringify!($field) {
-                                if let Ok(v) = f64::from_str(val_str) { params.$field = v; }
-                            }
-                        };
-                    }
-                    macro_rules! update_i32 {
-                        ($field:ident) => {
-                            if key == stringify!($field) {
-                                if let Ok(v) = i32::from_str(val_str) { params.$field = v; }
-                            }
-                        };
-                    }
-
-                    update_f64!(score_play_base);
-                    update_f64!(score_discard_base);
-                    update_f64!(score_hint_base);
-
-                    update_i32!(score_play_exponent_probability);
-                    update_f64!(score_play_by_playability_weight);
-                    update_f64!(score_play_badness_mistake_weight);
-                    update_f64!(score_play_can_play_5_sure);
-                    update_f64!(score_play_make_playable);
-                    update_f64!(score_play_make_playable_weighted_by_partner_knowledge);
-                    update_f64!(score_play_make_discardable);
-                    update_f64!(score_play_make_discardable_weighted_by_partner_knowledge);
-                    update_f64!(score_play_sure);
-                    update_f64!(score_play_focused_hint);
-
-                    update_i32!(score_discard_exponent_probability);
-                    update_f64!(score_discard_value_of_a_hint);
-                    update_f64!(score_discard_probability_weight);
-                    update_f64!(score_discard_badness_mistake_weight);
-                    update_f64!(score_discard_hints_low_weight);
-
-                    update_f64!(score_hint_focused_hint);
-                    update_i32!(score_hint_exponent_information_gain);
-                    update_f64!(score_hint_information_gain);
-                    update_f64!(score_hint_make_playable);
-                    update_f64!(score_hint_make_discardable);
-
-                    update_f64!(score_badness_discard_only_card_left_of_its_kind);
+                    params.set_from_str(parts[0], parts[1]);
                 }
             }
         } else {
@@ -147,95 +204,273 @@ impl Params {
         }
         params
     }
+
+    // loads the param set under the header [name] from a file holding several named sets:
+    //   [aggressive]
+    //   score_play_base = 2.0
+    //   [cautious]
+    //   ...
+    // keys missing from the section keep their default value
+    pub fn load_named(filename: &str, name: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(filename)
+            .map_err(|e| format!("Could not read params file {}: {}", filename, e))?;
+
+        let mut params = Params::default();
+        let mut section_found = false;
+        let mut in_section = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') && line.ends_with(']') {
+                in_section = line[1..line.len()-1].trim() == name;
+                section_found |= in_section;
+                continue;
+            }
+            if !in_section { continue; }
+            let parts: Vec<&str> = line.split('=').map(|s| s.trim()).collect();
+            if parts.len() == 2 {
+                params.set_from_str(parts[0], parts[1]);
+            }
+        }
+
+        if section_found {
+            Ok(params)
+        } else {
+            Err(format!("No param set named [{}] in {}", name, filename))
+        }
+    }
+    // FNV-1a hash over the bits of every value, changes whenever any param changes and is
+    // the same on every build and platform, so archived fingerprints stay comparable
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+
+        macro_rules! hash_f64 {
+            ($field:ident) => { feed(&self.$field.to_bits().to_le_bytes()); };
+        }
+        macro_rules! hash_i32 {
+            ($field:ident) => { feed(&self.$field.to_le_bytes()); };
+        }
+
+        hash_f64!(score_play_base);
+        hash_f64!(score_discard_base);
+        hash_f64!(score_hint_base);
+
+        hash_i32!(score_play_exponent_probability);
+        hash_f64!(score_play_by_playability_weight);
+        hash_f64!(score_play_badness_mistake_weight);
+        hash_f64!(score_play_can_play_5_sure);
+        hash_f64!(score_play_make_playable);
+        hash_f64!(score_play_make_playable_weighted_by_partner_knowledge);
+        hash_f64!(score_play_make_discardable);
+        hash_f64!(score_play_make_discardable_weighted_by_partner_knowledge);
+        hash_f64!(score_play_sure);
+        hash_f64!(score_play_focused_hint);
+        hash_f64!(score_play_finesse);
+
+        hash_i32!(score_discard_exponent_probability);
+        hash_f64!(score_discard_value_of_a_hint);
+        hash_f64!(score_discard_probability_weight);
+        hash_f64!(score_discard_badness_mistake_weight);
+        hash_f64!(score_discard_hints_low_weight);
+
+        hash_f64!(score_hint_focused_hint);
+        hash_i32!(score_hint_exponent_information_gain);
+        hash_f64!(score_hint_information_gain);
+        hash_f64!(score_hint_make_playable);
+        hash_f64!(score_hint_make_discardable);
+
+        hash_f64!(score_badness_discard_only_card_left_of_its_kind);
+
+        hash
+    }
+}
+
+// count / total as a probability. the total is 0 when a simulated hint does not touch the slot: the
+// hinted color or value and the slot's possibilities have no card in common. that counts as 0 instead
+// of turning every score into NaN
+fn ratio(count: u32, total: u32) -> f64 {
+    if total == 0 { 0.0 } else { count as f64 / total as f64 }
 }
 
 pub struct Robert { 
     hints_remaining: u8,
+    max_hints: u8, // the starting hint tokens, a discard never regains more
     mistakes_made: u8,
-    fireworks: [u8; 5],
+    max_mistakes: u8, // the game is lost on this many mistakes
+    fireworks: [u8; 6],
     my_hand_knowledge: Vec<DeckSubset>,
     partner_hand: Vec<Card>,
     partner_hand_knowledge: Vec<DeckSubset>,
     cards_not_seen: DeckSubset,
+    discarded_cards: Vec<Card>, // discarded and misplayed cards
     focused_hint: Option<usize>, // potentially the index to the card that was hinted directly
+    finesse_target: Option<usize>, // the slot a finesse hint said holds the card connecting to the hinted one
     params: Params, // holds the strategy parameters
+    conventions: bool, // if false, single card hints are not read as "play this", for partners that do not know the convention
+    finesse: bool, // if true, a single card hint to a card one step beyond playable points at the connecting card
+    agreed_conventions: bool, // the conventions used this game, what we and the partner both play (see negotiate)
+    agreed_finesse: bool,
+    number_of_players: usize,
+}
+
+impl Default for Robert {
+    fn default() -> Self {
+        Robert::new()
+    }
 }
 
 impl Robert {
+    // plays with robert_params.txt. The file is read once, when the first Robert is built,
+    // so benchmarks building a Robert for every game don't read it every time
     pub fn new() -> Self {
-        Robert {
-            hints_remaining: 8,
-            mistakes_made: 0,
-            fireworks: [0; 5],
-            my_hand_knowledge: vec![DeckSubset::new_full(); 5],
-            partner_hand: Vec::new(),
-            partner_hand_knowledge: vec![DeckSubset::new_full(); 5],
-            cards_not_seen: DeckSubset::new_full(),
-            focused_hint: None,
-            params: Params::load_from_file_or_default("robert_params.txt")
-        }
+        static DEFAULT_PARAMS: OnceLock<Params> = OnceLock::new();
+        Robert::new_with_params(*DEFAULT_PARAMS.get_or_init(|| Params::load_from_file_or_default(&resolve_config_path("robert_params.txt"))))
+    }
+
+    // plays with the params in the file, the defaults for every key the file does not set
+    pub fn new_from_file(path: &str) -> Self {
+        Robert::new_with_params(Params::load_from_file_or_default(path))
     }
-    
+
     pub fn new_with_params(params: Params) -> Self {
         Robert {
             hints_remaining: 8,
+            max_hints: 8,
             mistakes_made: 0,
-            fireworks: [0; 5],
+            max_mistakes: 3,
+            fireworks: [0; 6],
             my_hand_knowledge: vec![DeckSubset::new_full(); 5],
             partner_hand: Vec::new(),
             partner_hand_knowledge: vec![DeckSubset::new_full(); 5],
             cards_not_seen: DeckSubset::new_full(),
+            discarded_cards: Vec::new(),
             focused_hint: None,
+            finesse_target: None,
             params,
+            conventions: true,
+            finesse: false,
+            agreed_conventions: true,
+            agreed_finesse: false,
+            number_of_players: 2,
         }
     }
 
-    fn all_possible_moves(&self) -> Vec<Move> {
-        let mut all_moves: Vec<Move> = Vec::new();
-        // play and discard moves
-        for i in 0..self.my_hand_knowledge.len() {
-            all_moves.push(Move::Play(i));
-            all_moves.push(Move::Discard(i));
-        }
-        // hint moves
-        if self.hints_remaining > 0 {
-            for value in 1..6 {
-                all_moves.push(Move::HintValue(value));
+    // a play or discard of a player that is not our partner, or a hint that is for neither of us
+    fn update_after_third_party_move(&mut self, mv_result: &MoveResult) {
+        match mv_result {
+            MoveResult::Play { drawn, .. } | MoveResult::Discard { drawn, .. } => {
+                if let Some((card, to_discard_pile)) = apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    if to_discard_pile && matches!(mv_result, MoveResult::Play { .. }) {
+                        self.mistakes_made += 1;
+                    }
+                    if to_discard_pile { self.discarded_cards.push(card); }
+                    self.cards_not_seen.remove_card(&card);
+                }
+                if let Some(new_card) = drawn { self.cards_not_seen.remove_card(new_card); }
             }
-            for color in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White] {
-                all_moves.push(Move::HintColor(color));
+            MoveResult::Hint { .. } => self.hints_remaining = self.hints_remaining.saturating_sub(1),
+        }
+    }
+
+    // only acts on what the hints say directly: hinted single cards are not played on trust,
+    // and hints are not chosen for the focus the partner would read into them
+    pub fn without_conventions(mut self) -> Self {
+        self.conventions = false;
+        self.agreed_conventions = false;
+        self
+    }
+
+    // reads and gives finesse hints: a hint touching only a card that can't be playable yet but can be
+    // one step beyond playable says that the leftmost other card of that hand connects to it,
+    // so that card is played next. Needs the partner to play the same convention
+    pub fn with_finesse(mut self) -> Self {
+        self.finesse = true;
+        self.agreed_finesse = true;
+        self
+    }
+
+    // the slot a finesse hint that touched only the card at hinted points at: the leftmost other slot
+    // no hint has touched yet. None if every other slot was hinted
+    fn finesse_slot(knowledge: &[DeckSubset], hinted: usize) -> Option<usize> {
+        (0..knowledge.len()).find(|&i| i != hinted && !is_touched(&knowledge[i]))
+    }
+
+    // the cards exactly one step beyond the top of their firework
+    fn one_beyond_playable_cards(&self) -> DeckSubset {
+        let mut cards = DeckSubset::new_empty();
+        for (color_index, &color) in GameVariant::Rainbow.colors().iter().enumerate() {
+            if self.fireworks[color_index] + 2 <= 5 {
+                cards = cards.union(&DeckSubset::from_color(color).intersect(&DeckSubset::from_value(self.fireworks[color_index] + 2)));
             }
         }
-        all_moves
+        cards
     }
 
-    fn exact_card_if_known(&self, idx: usize) -> Option<Card> {
-        // pick the first 1 in the knowledge bitset to get a potential card
-        // then check if this is really the only card in the knowledge
-        let knowledge = &self.my_hand_knowledge[idx];
-        // find position of first 1
-        let first_card_index = knowledge.0.trailing_zeros() as u8;
-        // the cards in the decksubset struct are ordered in the same order as Card takes them, so this index is directly usable
-        let card = Card::new(first_card_index);
-        let card_subset = DeckSubset::from_card_type(&card);
-        knowledge.is_subset(&card_subset).then(|| card)
+    // a single card hint is read as a finesse if the touched card, as far as the receiver can tell,
+    // is not playable but might be the card one step beyond
+    fn reads_as_finesse(&self, possibilities: &DeckSubset) -> bool {
+        possibilities.intersect(&self.playable_cards()).is_empty()
+            && !possibilities.intersect(&self.one_beyond_playable_cards()).is_empty()
+    }
 
+    // true if this hint, touching only the partner card at idx, is a finesse the partner reads correctly:
+    // the card is one step beyond playable and the finesse slot holds the connecting card
+    fn hint_sets_up_finesse(&self, idx: usize, hint: &Move) -> bool {
+        let card = self.partner_hand[idx];
+        let color_index = card.get_color().index();
+        if card.get_value() != self.fireworks[color_index] + 2 {
+            return false;
+        }
+        let Some(slot) = Self::finesse_slot(&self.partner_hand_knowledge, idx) else { return false };
+        let connecting = self.partner_hand[slot];
+        connecting.get_color() == card.get_color() && connecting.get_value() == card.get_value() - 1
+            && self.reads_as_finesse(&knowledge_after_hint(&self.partner_hand_knowledge[idx], hint).intersect(&self.cards_not_seen_by_partner()))
+    }
+
+    // the cards the partner can't see: their own hand is hidden from them, ours is not. we don't know
+    // our own cards, so they stay in. that only leaves the partner more possibilities than they have,
+    // a finesse then looks less certain than it is but never more
+    fn cards_not_seen_by_partner(&self) -> DeckSubset {
+        let mut unseen = self.cards_not_seen;
+        for card in &self.partner_hand {
+            unseen.add_card(card);
+        }
+        unseen
+    }
+
+    // number of unseen cards that the card in slot idx could still be
+    fn possibilities_for_slot(&self, idx: usize) -> u32 {
+        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).count()
+    }
+
+    // true if all remaining possibilities of slot idx are the same kind of card
+    fn is_slot_resolved(&self, idx: usize) -> bool {
+        resolved_card(&self.my_hand_knowledge[idx], &self.cards_not_seen).is_some()
+    }
+
+    fn exact_card_if_known(&self, idx: usize) -> Option<Card> {
+        if !self.is_slot_resolved(idx) {
+            return None;
+        }
+        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).iter_cards().next()
     }
 
     fn playable_cards(&self) -> DeckSubset {
+        Self::playable_cards_with(&self.fireworks)
+    }
+
+    // the playable cards for any fireworks, to ask what would be playable after a play
+    fn playable_cards_with(fireworks: &[u8; 6]) -> DeckSubset {
         let mut playable = DeckSubset::new_empty();
-        for (color_index, &top_value) in self.fireworks.iter().enumerate() {
+        for (color_index, &top_value) in fireworks.iter().enumerate() {
             if top_value < 5 {
-                let color = match color_index {
-                    0 => Color::Red,
-                    1 => Color::Green,
-                    2 => Color::Blue,
-                    3 => Color::Yellow,
-                    4 => Color::White,
-                    _ => unreachable!(),
-                };
-                let next_card_subset = DeckSubset::from_color(color)
-                    .intersect(&DeckSubset::from_value(top_value + 1));
+                let color = Color::from_index(color_index);
+                let next_card_subset = DeckSubset::from_card_type(&Card::from_color_value(color, top_value + 1));
                 playable = playable.union(&next_card_subset);
             }
         }
@@ -243,20 +478,16 @@ impl Robert {
     }
 
     fn discardable_cards(&self) -> DeckSubset {
+        Self::discardable_cards_with(&self.fireworks)
+    }
+
+    fn discardable_cards_with(fireworks: &[u8; 6]) -> DeckSubset {
         // a card is discardable if fireworks already has it or higher
         let mut discardable = DeckSubset::new_empty();
-        for (color_index, &top_value) in self.fireworks.iter().enumerate() {
+        for (color_index, &top_value) in fireworks.iter().enumerate() {
             for value in 1..=top_value {
-                let color = match color_index {
-                    0 => Color::Red,
-                    1 => Color::Green,
-                    2 => Color::Blue,
-                    3 => Color::Yellow,
-                    4 => Color::White,
-                    _ => unreachable!(),
-                };
-                let next_card_subset = DeckSubset::from_color(color)
-                    .intersect(&DeckSubset::from_value(value));
+                let color = Color::from_index(color_index);
+                let next_card_subset = DeckSubset::from_card_type(&Card::from_color_value(color, value));
                 discardable = discardable.union(&next_card_subset);
             }
         }
@@ -267,18 +498,24 @@ impl Robert {
     fn probability_playable(&self, idx: usize) -> f64 {
         // divide number of playable cards in knowledge by total number of cards in knowledge
         // intersect with cards not seen to only count cards that could still be in hand
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&self.playable_cards())).0.count_ones() as f64 /
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).0.count_ones() as f64
+        ratio(self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&self.playable_cards())).count(), self.possibilities_for_slot(idx))
+    }
+    // the same from the other side: the possibilities that would be a mistake or a lost card
+    fn probability_not_playable(&self, idx: usize) -> f64 {
+        ratio(self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).difference(&self.playable_cards()).count(), self.possibilities_for_slot(idx))
+    }
+    fn probability_not_discardable(&self, idx: usize) -> f64 {
+        ratio(self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).difference(&self.discardable_cards()).count(), self.possibilities_for_slot(idx))
     }
     fn probability_discardable(&self, idx: usize) -> f64 {
         // divide number of discardable cards in knowledge by total number of cards in knowledge
         // intersect with cards not seen to only count cards that could still be in hand
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&&self.discardable_cards())).0.count_ones() as f64 /
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).0.count_ones() as f64
+        ratio(self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&self.discardable_cards())).count(), self.possibilities_for_slot(idx))
     }
 
-    // the probability of a card being playable/discardable based on knowledge from partners perspective
-    fn partner_probability_playable(&self, idx: usize, hint: Option<Move>) -> f64 {
+    // the probability of a card being playable/discardable based on knowledge from partners perspective,
+    // with the given fireworks, which are not the current ones when a play of ours is simulated
+    fn partner_probability_playable(&self, idx: usize, hint: Option<Move>, fireworks: &[u8; 6]) -> f64 {
         // if we pass a hint, then we want to know the probability after this hint is given, so we intersect with it
         let hint_subset = if let Some(h) = hint {
             match h {
@@ -291,10 +528,9 @@ impl Robert {
         };
         // divide number of playable cards in knowledge by total number of cards in knowledge
         // intersect with cards not seen to only count cards that could still be in hand
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&self.playable_cards()))).0.count_ones() as f64 /
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).0.count_ones() as f64
+        ratio(hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&Self::playable_cards_with(fireworks)))).count(), hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).count())
     }
-    fn partner_probability_discardable(&self, idx: usize, hint: Option<Move>) -> f64 {
+    fn partner_probability_discardable(&self, idx: usize, hint: Option<Move>, fireworks: &[u8; 6]) -> f64 {
         // if we pass a hint, then we want to know the probability after this hint is given, so we intersect with it
         let hint_subset = if let Some(h) = hint {
             match h {
@@ -307,39 +543,44 @@ impl Robert {
         };
         // divide number of discardable cards in knowledge by total number of cards in knowledge
         // intersect with cards not seen to only count cards that could still be in hand
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&&self.discardable_cards()))).0.count_ones() as f64 /
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).0.count_ones() as f64
+        ratio(hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&Self::discardable_cards_with(fireworks)))).count(), hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).count())
+    }
+
+    // how many copies of this kind of card are on the discard pile
+    fn count_in_discard(&self, card_type: Card) -> usize {
+        self.discarded_cards.iter()
+            .filter(|c| c.get_color() == card_type.get_color() && c.get_value() == card_type.get_value())
+            .count()
     }
 
-    // the probability of being the only card left of its kind
+    // the probability of being the only card left of its kind.
+    // a kind only counts if all other copies are on the discard pile, not if the partner holds one or it was played
     fn probability_only_card_left_of_its_kind(&self, idx: usize) -> f64{
-        let mut number_only_card_left = 0;
-        for value in 0..4 {
-            for color_idx in  0..4 {
-                let card_subset = DeckSubset::from_card_type(&Card::from_value_color_idx(value, color_idx));
-                if card_subset.intersect(&self.my_hand_knowledge[idx]).intersect(&self.cards_not_seen).0.count_ones() == 1 {
-                    number_only_card_left += 1;
-                }
+        let mut number_only_card_left = 0u32;
+        for card_type in Card::all_types() {
+            if self.my_hand_knowledge[idx].intersect(&self.cards_not_seen).remaining_copies(card_type) == 1
+                && self.count_in_discard(card_type) + 1 == card_type.max_copies() as usize {
+                number_only_card_left += 1;
             }
         }
-        number_only_card_left as f64 / self.my_hand_knowledge[idx].intersect(&self.cards_not_seen).0.count_ones() as f64
+        ratio(number_only_card_left, self.possibilities_for_slot(idx))
     }
 
     fn number_of_cards_excluded_by_color_hint(&self, color: Color) -> [u8; 5] {
         let mut number_of_cards_excluded_array = [0u8; 5];
         for i in 0..self.partner_hand_knowledge.len() {
-            if self.partner_hand[i].get_color() == color {
+            if self.partner_hand[i].get_color().touched_by(color) {
                 // intersect the subset of all cards that could be in this hand position by the set of cards which do not have this color
                 // this is the number of cards that has been excluded by this hint for this card
                 let number_of_cards_excluded = self.cards_not_seen
                                         .intersect(&self.partner_hand_knowledge[i])
-                                        .intersect(&DeckSubset::from_color_inverted(color)).0.count_ones();
+                                        .intersect(&DeckSubset::from_color_inverted(color)).count();
                 number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
             } else {
                 // in this case, the partner learns that this card is not of this color, i.e. all cards of this color are excluded
                 let number_of_cards_excluded = self.cards_not_seen
                                         .intersect(&self.partner_hand_knowledge[i])
-                                        .intersect(&DeckSubset::from_color(color)).0.count_ones();
+                                        .intersect(&DeckSubset::from_color(color)).count();
                 number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
             }
         }
@@ -354,13 +595,13 @@ impl Robert {
                 // this is the number of cards that has been excluded by this hint for this card
                 let number_of_cards_excluded = self.cards_not_seen
                                         .intersect(&self.partner_hand_knowledge[i])
-                                        .intersect(&&DeckSubset::from_value_inverted(value)).0.count_ones();
+                                        .intersect(&DeckSubset::from_value_inverted(value)).count();
                 number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
             } else {
                 // in this case, the partner learns that this card is not of this value, i.e. all cards of this value are excluded
                 let number_of_cards_excluded = self.cards_not_seen
                                         .intersect(&self.partner_hand_knowledge[i])
-                                        .intersect(&&DeckSubset::from_value(value)).0.count_ones();
+                                        .intersect(&DeckSubset::from_value(value)).count();
                 number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
             }
         }
@@ -382,7 +623,7 @@ impl Robert {
     // Minus points if:
     //  - probability of not being playable is high:
     //      weighted by how bad a mistake would be (more mistakes already made means a mistake is worse)
-    fn score_play(&mut self, idx: usize) -> f64 {
+    fn score_play(&self, idx: usize) -> f64 {
         let mut score = 0.0;
 
         // play the focused hint card:
@@ -390,9 +631,14 @@ impl Robert {
             score += self.params.score_play_focused_hint;
         }
 
+        // play the card a finesse pointed at
+        if self.finesse_target == Some(idx) {
+            score += self.params.score_play_finesse;
+        }
+
         // give score for probability of being playable
         let probability_playable = self.probability_playable(idx);
-        if probability_playable < 1.0-10e-15 && self.mistakes_made == 2 { return 0.0 } // do not lose the game
+        if probability_playable < 1.0-10e-15 && self.mistakes_made + 1 >= self.max_mistakes { return 0.0 } // do not lose the game
         score += probability_playable.powi(self.params.score_play_exponent_probability) * self.params.score_play_by_playability_weight;
 
         // extra points if we are sure
@@ -403,10 +649,11 @@ impl Robert {
         // remove score for probability of not being playable, weighted seprately by how bad a mistake would be
         // if we can still make mistakes, then we can play riskier
         // +5 so that this factor does not have too much of an impact. Otherwise we might be too risky at the start
-        score -= (1.0-probability_playable) * ((self.mistakes_made+5) as f64) * self.params.score_play_badness_mistake_weight;
+        let probability_not_playable = self.probability_not_playable(idx);
+        score -= probability_not_playable * ((self.mistakes_made+5) as f64) * self.params.score_play_badness_mistake_weight;
 
         // removes score if the card might be the only one of its kind left
-        score -= (1.0-probability_playable) * self.probability_only_card_left_of_its_kind(idx) * self.params.score_badness_discard_only_card_left_of_its_kind;
+        score -= probability_not_playable * self.probability_only_card_left_of_its_kind(idx) * self.params.score_badness_discard_only_card_left_of_its_kind;
 
         // give a bonus if it makes a card in partner's hand playable
         // weighted by probability of that card being playable from their perspective
@@ -414,7 +661,7 @@ impl Robert {
 
         if let Some(card) = self.exact_card_if_known(idx) {
             let color = card.get_color();
-            let color_index = color as usize;
+            let color_index = color.index();
             let value = card.get_value();
             // first check if the card is even playable
             if value != self.fireworks[color_index] + 1 {
@@ -422,6 +669,9 @@ impl Robert {
             }
             // the value of the new card that would now be playable
             let playable_value = self.fireworks[color_index] + 1;
+            // the fireworks after our play, to ask what the partner can do then
+            let mut fireworks_after_play = self.fireworks;
+            fireworks_after_play[color_index] += 1;
             if playable_value == 6 {
                 // we know it is a 5 and we can play it, that a huge bonus
                 // we dont need to check if this makes a card in partners hand playable, because it is a 5
@@ -438,22 +688,14 @@ impl Robert {
                 if partner_card_color == color && partner_card_value == playable_value {
                     // card would be playable now
                     score += self.params.score_play_make_playable; // base bonus for making a card playable
-                    // temporarily add this card to he fireworks so the probability function works
-                    // might change later to just pass the fireworks to probability function, but this way the data stays in place
-                    self.fireworks[color_index] += 1;
-                    let partner_prob_playable = self.partner_probability_playable(card_idx, None);
-                    self.fireworks[color_index] -= 1;
+                    let partner_prob_playable = self.partner_probability_playable(card_idx, None, &fireworks_after_play);
                     // bonus weighted by probability of them knowing it is playable
                     score += partner_prob_playable * self.params.score_play_make_playable_weighted_by_partner_knowledge;
                 }
                 if partner_card_color == color && partner_card_value < playable_value {
                     // this card can now be discarded
                     score += self.params.score_play_make_discardable;
-                    // temporarily add this card to he fireworks so the probability function works
-                    // might change later to just pass the fireworks to probability function, but this way the data stays in place
-                    self.fireworks[color_index] += 1;
-                    let partner_prob_playable = self.partner_probability_discardable(card_idx, None);
-                    self.fireworks[color_index] -= 1;
+                    let partner_prob_playable = self.partner_probability_discardable(card_idx, None, &fireworks_after_play);
                     // bonus weighted by probability of them knowing it is discardable
                     score += partner_prob_playable * self.params.score_play_make_discardable_weighted_by_partner_knowledge;
                 }
@@ -481,13 +723,14 @@ impl Robert {
         score += probability_discardable.powi(self.params.score_discard_exponent_probability) * self.params.score_discard_probability_weight;
 
         // give score if hints are low
-        score += (8-self.hints_remaining) as f64 * self.params.score_discard_hints_low_weight;
+        score += (self.max_hints - self.hints_remaining) as f64 * self.params.score_discard_hints_low_weight;
 
         // remove score for probability of not being discardable
-        score -= (1.0-probability_discardable) * self.params.score_discard_badness_mistake_weight;
+        let probability_not_discardable = self.probability_not_discardable(idx);
+        score -= probability_not_discardable * self.params.score_discard_badness_mistake_weight;
 
         // removes score if the card might be the only one of its kind left
-        score -= (1.0-probability_discardable) * self.probability_only_card_left_of_its_kind(idx) * self.params.score_badness_discard_only_card_left_of_its_kind;
+        score -= probability_not_discardable * self.probability_only_card_left_of_its_kind(idx) * self.params.score_badness_discard_only_card_left_of_its_kind;
 
         if score<0. { 0. } else { score }
     }
@@ -505,17 +748,15 @@ impl Robert {
 
         let cards_affected_indices: Vec<usize> = match hint {
             Move::HintColor(color) => (0..self.partner_hand.len())
-                .filter(|x| self.partner_hand[*x].get_color() == *color)
+                .filter(|x| self.partner_hand[*x].get_color().touched_by(*color))
                 .collect(),
             Move::HintValue(value) => (0..self.partner_hand.len())
                 .filter(|x| self.partner_hand[*x].get_value() == *value)
                 .collect(),
             _ => unreachable!(),
         };
-
-        if cards_affected_indices.is_empty() {
-            return -1000.0; 
-        }
+        // the hints come from legal_moves, which only lists hints that touch a card
+        debug_assert!(!cards_affected_indices.is_empty(), "{:?} touches no card of the partner", hint);
 
         let mut score = 0.0;
         let information_gained_array = match hint {
@@ -525,23 +766,26 @@ impl Robert {
         };
 
         for i in 0..self.partner_hand_knowledge.len() {
-            score += (1.0 + (information_gained_array[i] as f64 / self.partner_hand_knowledge[i].0.count_ones() as f64)  
+            score += (1.0 + ratio(information_gained_array[i] as u32, self.partner_hand_knowledge[i].count())  
                                 * self.params.score_hint_information_gain).powi(self.params.score_hint_exponent_information_gain) - 1.0;
         }
 
         // Focused Hint Logic
-        if cards_affected_indices.len() == 1 {
+        if self.agreed_conventions && cards_affected_indices.len() == 1 {
             let idx = cards_affected_indices[0];
             let card_affected = self.partner_hand[idx];
             let card_affected_color = card_affected.get_color();
             let card_affected_value = card_affected.get_value();
             
-            if card_affected_value == self.fireworks[card_affected_color as usize] + 1 {
+            if card_affected_value == self.fireworks[card_affected_color.index()] + 1 {
                 // Only add score if partner knows about it
-                if self.partner_probability_playable(idx, None) < 0.99 {
+                if self.partner_probability_playable(idx, None, &self.fireworks) < 0.99 {
                     score += self.params.score_hint_focused_hint;
                 }
-            } else if card_affected_value > self.fireworks[card_affected_color as usize] + 1 {
+            } else if self.agreed_finesse && self.hint_sets_up_finesse(idx, hint) {
+                // the partner plays the connecting card first
+                score += self.params.score_hint_focused_hint;
+            } else if card_affected_value > self.fireworks[card_affected_color.index()] + 1 {
                  // Bad hint
                 score -= self.params.score_hint_focused_hint;
             }
@@ -551,12 +795,12 @@ impl Robert {
         for i in 0..self.partner_hand_knowledge.len() {
             // Check if becoming playable
             // Wichtig: Wir prüfen, ob die Karte VORHER noch nicht sicher spielbar war
-            if self.partner_probability_playable(i, Some(*hint)) > 0.99 && self.partner_probability_playable(i, None) < 0.99 {
+            if self.partner_probability_playable(i, Some(*hint), &self.fireworks) > 0.99 && self.partner_probability_playable(i, None, &self.fireworks) < 0.99 {
                 score += self.params.score_hint_make_playable;
             }
             
             // Check if becoming discardable
-            if self.partner_probability_discardable(i, Some(*hint)) > 0.99 && self.partner_probability_discardable(i, None) < 0.99 {
+            if self.partner_probability_discardable(i, Some(*hint), &self.fireworks) > 0.99 && self.partner_probability_discardable(i, None, &self.fireworks) < 0.99 {
                 score += self.params.score_hint_make_discardable;
             }
         }
@@ -564,8 +808,31 @@ impl Robert {
         score
     }
 
+    // a hint touched only the card at idx of our hand: if it can be a finesse, the finesse slot
+    // is played next, otherwise the hinted card is
+    fn read_focused_hint(&mut self, idx: usize) {
+        let possibilities = self.my_hand_knowledge[idx].intersect(&self.cards_not_seen);
+        let finesse_slot = Self::finesse_slot(&self.my_hand_knowledge, idx);
+        if self.agreed_finesse && finesse_slot.is_some() && self.reads_as_finesse(&possibilities) {
+            self.finesse_target = finesse_slot;
+        } else {
+            self.focused_hint = Some(idx);
+        }
+    }
+
+    // the order equally scored moves are preferred in, lowest first: plays, then hints, then discards.
+    // plays and discards of lower slots first, value hints before color hints, lower values and colors first
+    fn tie_break_rank(mv: &Move) -> (u8, usize) {
+        match mv {
+            Move::Play(idx) => (0, *idx),
+            Move::HintValue(value) => (1, *value as usize),
+            Move::HintColor(color) => (1, 10 + color.index()),
+            Move::Discard(idx) => (2, *idx),
+        }
+    }
+
     // entry point for the score functions
-    fn score_move(&mut self, mv: &Move) -> f64 {
+    fn score_move(&self, mv: &Move) -> f64 {
         let score = match mv {
             Move::Play(idx) => self.score_play(*idx) * self.params.score_play_base,
             Move::Discard(idx) => self.score_discard(*idx) * self.params.score_discard_base,
@@ -577,102 +844,132 @@ impl Robert {
 }
 
 impl Strategy for Robert {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>) {
-        self.partner_hand = other_player_hand.clone();
-        for card in other_player_hand {
+    fn name(&self) -> &'static str {
+        match (self.conventions, self.finesse) {
+            (false, _) => "RobertDirect",
+            (true, false) => "Robert",
+            (true, true) => "RobertFinesse",
+        }
+    }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.hints_remaining = config.starting_hints;
+        self.max_hints = config.starting_hints;
+        self.max_mistakes = config.max_mistakes;
+        self.number_of_players = other_player_hands.len() + 1;
+        self.partner_hand = other_player_hands[0].clone();
+        self.my_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        self.partner_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        for card in other_player_hands.iter().flatten() {
             self.cards_not_seen.remove_card(card);
         }
     }
 
-    fn decide_move(&mut self) -> Move {
-        let all_moves = self.all_possible_moves();
+    fn reset(&mut self) {
+        let (conventions, finesse) = (self.conventions, self.finesse);
+        *self = Robert { conventions, finesse, agreed_conventions: conventions, agreed_finesse: finesse, ..Robert::new_with_params(self.params) };
+    }
+
+    // we keep the conventions both of us play and drop the rest. Only a RobertFinesse gives and reads
+    // finesses, and only a RobertDirect neither reads nor gives focused hints. Gemini and ChatGPT mean
+    // "play this" with a single card hint as well, so their hints are trusted like a Robert's
+    fn negotiate(&mut self, partner_name: &str) {
+        let (conventions, finesse) = match partner_name {
+            "RobertDirect" => (false, false),
+            "RobertFinesse" => (true, true),
+            _ => (true, false),
+        };
+        self.agreed_conventions = self.conventions && conventions;
+        self.agreed_finesse = self.finesse && finesse;
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+        self.hints_remaining = view.hints_remaining;
+        self.mistakes_made = view.mistakes_made;
+        self.fireworks = view.fireworks;
+        let all_moves = legal_moves(view, &self.partner_hand);
 
-        // we find the max score move by interpreting the f64 as a bit vector.
-        // If the sign bit is 0, the number is positive and we flip that bit
-        // Otherwise, we flip all bits to reverse the 2's complement
+        // score every move once and drop NaN and infinite scores, they would win or lose the comparison arbitrarily
+        let scored_moves: Vec<(Move, f64)> = all_moves
+            .iter()
+            .map(|m| (*m, self.score_move(m)))
+            .filter(|(_, score)| score.is_finite())
+            .collect();
 
-        *all_moves
+        // the best score wins. equal scores go to the move that comes first in tie_break_rank,
+        // so the choice does not depend on the order legal_moves lists the moves in
+        scored_moves
             .iter()
-            .max_by_key(|&m| { let b = self.score_move(m).to_bits() as i64; b ^ (b >> 63 & i64::MAX) })
-            .expect("There must be at least one move")
+            .max_by(|(move_a, score_a), (move_b, score_b)| {
+                score_a.total_cmp(score_b).then_with(|| Self::tie_break_rank(move_b).cmp(&Self::tie_break_rank(move_a)))
+            })
+            .map(|(m, _)| *m)
+            // nothing could be scored
+            .unwrap_or_else(|| safe_fallback_move(self.my_hand_knowledge.len(), &self.partner_hand, self.hints_remaining, view.discard_allowed))
+    }
+
+    fn play_probability(&self, idx: usize) -> Option<f64> {
+        Some(self.probability_playable(idx))
     }
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
         match mv {
-            Move::Play(idx) => {
-                match mv_result {
-                    MoveResult::Play(success, card_played, _) => {
-                        if *success {
-                            // Update fireworks
-                            let color_index = card_played.get_color() as usize;
-                            self.fireworks[color_index] += 1;
-                        } else {
-                            self.mistakes_made += 1;
-                        }
-                        // Remove played card knowledge
-                        self.my_hand_knowledge.remove(*idx);
-                        if got_new_card {
-                            self.my_hand_knowledge.push(DeckSubset::new_full());
-                        }
-                        // update cards not seen
-                        self.cards_not_seen.remove_card(card_played);
-                    },
-                    _ => unreachable!()
-                }
-                // if we played the focused hint, then its None now
-                if let Some(i) = self.focused_hint && i == *idx {
-                    self.focused_hint = None;
-                }
-                // if we played a card left of the focused hint, then we must shift it
-                if let Some(i) = self.focused_hint && i > *idx {
-                    self.focused_hint = Some(i-1);
-                }
-            }
-            Move::Discard(idx) => {
-                // Remove discarded card knowledge
-                self.my_hand_knowledge.remove(*idx);
-                if got_new_card {
-                    self.my_hand_knowledge.push(DeckSubset::new_full());
-                }
-                if self.hints_remaining < 8 {
-                    self.hints_remaining += 1;
-                }
-                // update cards not seen: the discarded card is now seen
-                let discarded_card = match mv_result {
-                    MoveResult::Discard(card_discarded, _) => card_discarded,
-                    _ => unreachable!()
+            Move::Play(idx) | Move::Discard(idx) => {
+                let Some((card, to_discard_pile)) = apply_own_card_move(&mut self.my_hand_knowledge, *idx, got_new_card.then(DeckSubset::new_full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) else {
+                    unreachable!()
                 };
-                self.cards_not_seen.remove_card(discarded_card);
+                // a played card that lands on the discard pile was a mistake
+                if to_discard_pile && matches!(mv, Move::Play(_)) {
+                    self.mistakes_made += 1;
+                }
+                // update cards not seen: the card is now seen
+                self.cards_not_seen.remove_card(&card);
+                if to_discard_pile { self.discarded_cards.push(card); }
 
-                // if we discarded the focused hint, then its None now
+                // if we played or discarded the focused hint, then its None now
                 if let Some(i) = self.focused_hint && i == *idx {
                     self.focused_hint = None;
                 }
-                // if we discarded a card left of the focused hint, then we must shift it
+                // if the card was left of the focused hint, then we must shift it
                 if let Some(i) = self.focused_hint && i > *idx {
                     self.focused_hint = Some(i-1);
                 }
+                // the same for the finesse target
+                match self.finesse_target {
+                    Some(i) if i == *idx => self.finesse_target = None,
+                    Some(i) if i > *idx => self.finesse_target = Some(i-1),
+                    _ => (),
+                }
             }
             Move::HintColor(color) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update partner's hand knowledge based on hint
                 match mv_result {
-                    MoveResult::Hint(indices) => {
+                    MoveResult::Hint { indices } => {
                         for i in indices.iter() {
                             self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
                         }
+                        // the untouched cards, all of them for an empty hint, are not of this color
+                        for i in (0..self.partner_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
+                            self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_color_inverted(*color));
+                        }
                     },
                     _ => ()
                 }
             }
             Move::HintValue(value) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update partner's hand knowledge based on hint
                 match mv_result {
-                    MoveResult::Hint(indices) => {
+                    MoveResult::Hint { indices } => {
                         for i in indices.iter() {
                             self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
                         }
+                        // the untouched cards, all of them for an empty hint, are not of this value
+                        for i in (0..self.partner_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
+                            self.partner_hand_knowledge[i] = self.partner_hand_knowledge[i].intersect(&DeckSubset::from_value_inverted(*value));
+                        }
                     },
                     _ => ()
                 }
@@ -680,18 +977,23 @@ impl Strategy for Robert {
         }
     }
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult) {
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        if !concerns_partner_or_me(player_offset, self.number_of_players, mv) {
+            self.update_after_third_party_move(mv_result);
+            return;
+        }
         match mv {
             Move::Play(idx) => {
                 match mv_result {
-                    MoveResult::Play(success, card_played, card_drawn) => {
+                    MoveResult::Play { success, card: card_played, drawn: card_drawn } => {
                         self.cards_not_seen.remove_card(card_played); // both see this card
                         if *success {
                             // Update fireworks
-                            let color_index = card_played.get_color() as usize;
+                            let color_index = card_played.get_color().index();
                             self.fireworks[color_index] += 1;
                         } else {
                             self.mistakes_made += 1;
+                            self.discarded_cards.push(*card_played);
                         }
                         // Remove played card knowledge and hand and add new card if drawn
                         self.partner_hand_knowledge.remove(*idx);
@@ -707,9 +1009,10 @@ impl Strategy for Robert {
             }
             Move::Discard(idx) => {
                 match mv_result {
-                    MoveResult::Discard(card_discarded, card_drawn) => {
+                    MoveResult::Discard { card: card_discarded, drawn: card_drawn } => {
                         self.cards_not_seen.remove_card(card_discarded); // both see this card
-                        if self.hints_remaining < 8 {
+                        self.discarded_cards.push(*card_discarded);
+                        if self.hints_remaining < self.max_hints {
                             self.hints_remaining += 1;
                         }
                         // Remove played card knowledge and hand and add new card if drawn
@@ -725,10 +1028,10 @@ impl Strategy for Robert {
                 }
             }
             Move::HintColor(color) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update own's hand knowledge based on hint
                 match mv_result {
-                    MoveResult::Hint(indices) => {
+                    MoveResult::Hint { indices } => {
                         // update the cards the hint was about
                         for i in indices.iter() {
                             self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
@@ -737,19 +1040,19 @@ impl Strategy for Robert {
                         for i in (0..self.my_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
                             self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_color_inverted(*color));
                         }
-                        // if the hint is only about one card, then it is a focused hint
-                        if indices.len() == 1 {
-                            self.focused_hint = Some(indices[0]);
+                        // if the hint is only about one card, then it is a focused hint, or a finesse
+                        if self.agreed_conventions && indices.len() == 1 {
+                            self.read_focused_hint(indices[0]);
                         }
                     },
                     _ => ()
                 }
             }
             Move::HintValue(value) => {
-                self.hints_remaining -= 1;
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update own's hand knowledge based on hint
                 match mv_result {
-                    MoveResult::Hint(indices) => {
+                    MoveResult::Hint { indices } => {
                         // update the cards the hint was about
                         for i in indices.iter() {
                             self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
@@ -758,9 +1061,9 @@ impl Strategy for Robert {
                         for i in (0..self.my_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
                             self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_value_inverted(*value));
                         }
-                        // if the hint is only about one card, then it is a focused hint
-                        if indices.len() == 1 {
-                            self.focused_hint = Some(indices[0]);
+                        // if the hint is only about one card, then it is a focused hint, or a finesse
+                        if self.agreed_conventions && indices.len() == 1 {
+                            self.read_focused_hint(indices[0]);
                         }
                     },
                     _ => ()
@@ -768,4 +1071,178 @@ impl Strategy for Robert {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::player::Player;
+
+    #[test]
+    fn changed_param_changes_the_fingerprint() {
+        let params = Params::default();
+        let mut changed = params;
+        changed.score_play_sure += 1.0;
+        assert_eq!(params.fingerprint(), Params::default().fingerprint());
+        assert_ne!(params.fingerprint(), changed.fingerprint());
+        let mut changed = params;
+        changed.score_hint_exponent_information_gain += 1;
+        assert_ne!(params.fingerprint(), changed.fingerprint());
+    }
+
+    // every section starts from the defaults, so a key set in one section does not leak into the next
+    #[test]
+    fn named_sections_load_independently() {
+        let path = std::env::temp_dir().join(format!("hanabi_named_params_{}.txt", std::process::id()));
+        fs::write(&path, "[aggressive]\nscore_play_sure = 7.5\n[cautious]\nscore_hint_exponent_information_gain = 9\n").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let aggressive = Params::load_named(filename, "aggressive").unwrap();
+        let cautious = Params::load_named(filename, "cautious").unwrap();
+        let unknown = Params::load_named(filename, "reckless");
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(aggressive.score_play_sure, 7.5);
+        assert_eq!(aggressive.score_hint_exponent_information_gain, Params::default().score_hint_exponent_information_gain);
+        assert_eq!(cautious.score_hint_exponent_information_gain, 9);
+        assert_eq!(cautious.score_play_sure, Params::default().score_play_sure);
+        assert!(unknown.is_err());
+    }
+
+    // a fresh slot could be any card we don't see, a hinted one only the unseen cards of its color
+    #[test]
+    fn hint_narrows_the_possibilities_of_a_slot() {
+        let mut robert = Robert::new();
+        robert.initialize(&[(0..50).step_by(10).map(Card::new).collect()], &GameConfig::for_players(2));
+        assert_eq!(robert.possibilities_for_slot(0), 45);
+
+        robert.update_after_other_player_move(1, &Move::HintColor(Color::Red), &MoveResult::Hint { indices: vec![0] });
+        // nine reds left besides the red 1 in the partner's hand, the other slots can be anything else
+        assert_eq!(robert.possibilities_for_slot(0), 9);
+        assert_eq!(robert.possibilities_for_slot(1), 36);
+    }
+
+    // with finesses off a hint to a card that can't be played yet counts against the hint, and a
+    // lone hinted card that could be a finesse is read as a plain play clue
+    #[test]
+    fn without_finesse_no_indirect_play_is_relied_on() {
+        // the partner holds the red 1 in slot 0 and the red 2 in slot 3, the only 2
+        let partner_hand = vec![Card::new(0), Card::new(15), Card::new(27), Card::new(3), Card::new(37)];
+        let config = GameConfig::for_players(2);
+        let mut direct = Robert::new();
+        let mut finesse = Robert::new().with_finesse();
+        direct.initialize(std::slice::from_ref(&partner_hand), &config);
+        finesse.initialize(std::slice::from_ref(&partner_hand), &config);
+
+        let hint = Move::HintValue(2);
+        assert!(finesse.hint_sets_up_finesse(3, &hint));
+        let bonus = finesse.score_hint(&hint) - direct.score_hint(&hint);
+        assert_eq!(bonus, 2.0 * direct.params.score_hint_focused_hint);
+
+        // our slot 2 is hinted alone as a 2 on empty fireworks
+        let result = MoveResult::Hint { indices: vec![2] };
+        direct.update_after_other_player_move(1, &hint, &result);
+        finesse.update_after_other_player_move(1, &hint, &result);
+        assert_eq!(direct.finesse_target, None);
+        assert_eq!(finesse.finesse_target, Some(0));
+    }
+
+    // the finesse slot is the leftmost slot no hint has touched: a hinted red 1 in slot 0 is played
+    // on its own, and with every other slot touched a lone hint is read as a plain play clue
+    #[test]
+    fn finesse_skips_touched_slots() {
+        let partner_hand = vec![Card::new(0), Card::new(15), Card::new(27), Card::new(3), Card::new(37)];
+        let mut finesse = Robert::new().with_finesse();
+        finesse.initialize(std::slice::from_ref(&partner_hand), &GameConfig::for_players(2));
+        finesse.partner_hand_knowledge[0] = DeckSubset::from_value(1);
+        assert_eq!(Robert::finesse_slot(&finesse.partner_hand_knowledge, 3), Some(1));
+        assert!(!finesse.hint_sets_up_finesse(3, &Move::HintValue(2)));
+
+        for i in [0, 1, 3, 4] {
+            finesse.my_hand_knowledge[i] = DeckSubset::from_color(Color::Green);
+        }
+        assert_eq!(Robert::finesse_slot(&finesse.my_hand_knowledge, 2), None);
+        finesse.update_after_other_player_move(1, &Move::HintValue(2), &MoveResult::Hint { indices: vec![2] });
+        assert_eq!(finesse.finesse_target, None);
+        assert_eq!(finesse.focused_hint, Some(2));
+    }
+
+    // simulating our play of a red 1 by passing the fireworks after it gives what mutating the board,
+    // asking, and restoring it gave, and leaves the board alone
+    #[test]
+    fn explicit_fireworks_match_a_mutated_board() {
+        let mut robert = Robert::new();
+        robert.initialize(&[vec![Card::new(3), Card::new(5), Card::new(13), Card::new(0), Card::new(40)]], &GameConfig::for_players(2));
+        robert.update_after_own_move(&Move::HintColor(Color::Red), &MoveResult::Hint { indices: vec![0, 1, 3] }, false);
+        let current = robert.fireworks;
+        let mut after_play = current;
+        after_play[Color::Red.index()] += 1;
+
+        for idx in 0..5 {
+            let explicit = robert.partner_probability_playable(idx, None, &after_play);
+            robert.fireworks[Color::Red.index()] += 1;
+            let mutated = robert.partner_probability_playable(idx, None, &robert.fireworks.clone());
+            robert.fireworks[Color::Red.index()] -= 1;
+            assert_eq!(explicit, mutated, "slot {}", idx);
+            assert_eq!(robert.partner_probability_playable(idx, None, &robert.fireworks.clone()), robert.partner_probability_playable(idx, None, &current));
+        }
+        assert_eq!(robert.fireworks, current);
+        // we see the red 1, 2 and 3 in the partner's hand, the hinted red slots are one of the 7 other reds:
+        // the two red 1s are playable now, only the red 2 once red is at 1
+        for idx in [0, 1, 3] {
+            assert_eq!(robert.partner_probability_playable(idx, None, &current), 2.0 / 7.0, "slot {}", idx);
+            assert_eq!(robert.partner_probability_playable(idx, None, &after_play), 1.0 / 7.0, "slot {}", idx);
+        }
+    }
+
+    // a slot without a single possibility makes every ratio over it 0/0, the move must still be legal
+    #[test]
+    fn slot_without_possibilities_still_gives_a_legal_move() {
+        let my_hand: Vec<Card> = (0..5).map(Card::new).collect();
+        let partner_hand: Vec<Card> = (10..15).map(Card::new).collect();
+        let players = vec![Player::new(Box::new(Robert::new())), Player::new(Box::new(Robert::new()))];
+        let mut game = Game::new_with_hands(players, vec![my_hand, partner_hand.clone()]);
+
+        let mut robert = Robert::new();
+        robert.initialize(&[partner_hand], &GameConfig::for_players(2));
+        robert.my_hand_knowledge[2] = DeckSubset::new_empty();
+        game.players[0].strategy = Box::new(robert);
+        let mv = game.decide_move();
+        assert_eq!(game.check_move(&mv), Ok(()), "Robert chose {:?}", mv);
+    }
+
+    // a NaN base makes every play score NaN. those plays are never chosen, and with every
+    // score NaN the fallback discard is taken
+    #[test]
+    fn nan_scores_are_never_chosen() {
+        let decide = |params: Params| {
+            let players = vec![Player::new(Box::new(Robert::new_with_params(params))), Player::new(Box::new(Robert::new()))];
+            let hands = vec![(0..5).map(Card::new).collect(), (10..15).map(Card::new).collect()];
+            let mut game = Game::new_with_hands(players, hands);
+            game.apply_move(Move::HintColor(Color::Green)).unwrap();
+            game.apply_move(Move::HintValue(1)).unwrap();
+            let mv = game.decide_move();
+            assert_eq!(game.check_move(&mv), Ok(()), "Robert chose {:?}", mv);
+            mv
+        };
+        // our red 1s are known as 1s, without the NaN Robert plays one
+        assert!(matches!(decide(Params::default()), Move::Play(_)));
+        let nan_plays = Params { score_play_base: f64::NAN, ..Params::default() };
+        assert!(!matches!(decide(nan_plays), Move::Play(_)));
+        let nan_everything = Params { score_play_base: f64::NAN, score_discard_base: f64::NAN, score_hint_base: f64::NAN, ..Params::default() };
+        assert_eq!(decide(nan_everything), Move::Discard(0));
+    }
+
+    // the white 5 is the only one of its kind from the start, the kind the color loops up to 4 missed
+    #[test]
+    fn known_white_five_is_the_last_of_its_kind() {
+        let mut robert = Robert::new();
+        robert.initialize(&[(10..15).map(Card::new).collect()], &GameConfig::for_players(2));
+        robert.my_hand_knowledge[0] = DeckSubset::from_color(Color::White).intersect(&DeckSubset::from_value(5));
+        assert_eq!(robert.probability_only_card_left_of_its_kind(0), 1.0);
+        // a white 4 still has its second copy somewhere
+        robert.my_hand_knowledge[1] = DeckSubset::from_color(Color::White).intersect(&DeckSubset::from_value(4));
+        assert_eq!(robert.probability_only_card_left_of_its_kind(1), 0.0);
+    }
+}
@@ -1,86 +1,68 @@
-use crate::enums::{Move, MoveResult, Color};
+use crate::enums::{Move, MoveResult, GameVariant};
 use crate::card::Card;
 use crate::strategy::Strategy;
 use crate::decksubset::DeckSubset;
-use std::fs;
-use std::str::FromStr;
+use crate::game::{GameConfig, GameView};
+use crate::strategy_utils::{apply_own_card_move, apply_card_result, apply_hint, concerns_partner_or_me, hint_touching, knowledge_after_hint, knowledge_after_miss, legal_hints, safe_fallback_move};
 
 // robert2.rs
 
-
+// Plays by a queue of focused hints: a hint that touches a single card means "play this card",
+// and the cards pointed at this way are played in the order they were hinted.
+// Without a focused hint to follow it plays what it knows is playable, points at playable partner cards
+// the same way and otherwise discards the card most likely to be useless.
 pub struct Robert2 {
     hints_remaining: u8,
+    max_hints: u8, // the starting hint tokens, a discard never regains more
     mistakes_made: u8,
-    fireworks: [u8; 5],
-    number_moves_made: u8,      // the number of moves made by this player before the current one
+    max_mistakes: u8, // the game is lost on this many mistakes
+    fireworks: [u8; 6],
     my_hand_knowledge: Vec<DeckSubset>,
     partner_hand: Vec<Card>,
     partner_hand_knowledge: Vec<DeckSubset>,
     cards_not_seen: DeckSubset,
     play_next: Vec<usize>,      // holding the results of the focused hints. These cards should be played in this order next
     partner_play_next: Vec<usize>, // holding the results of the focused hints for the partner. These cards should be played in this order next
+    number_of_players: usize,
+}
+
+impl Default for Robert2 {
+    fn default() -> Self {
+        Robert2::new()
+    }
 }
 
 impl Robert2 {
     pub fn new() -> Self {
         Robert2 {
             hints_remaining: 8,
+            max_hints: 8,
             mistakes_made: 0,
-            fireworks: [0; 5],
-            number_moves_made: 0,
+            max_mistakes: 3,
+            fireworks: [0; 6],
             my_hand_knowledge: vec![DeckSubset::new_full(); 5],
             partner_hand: Vec::new(),
             partner_hand_knowledge: vec![DeckSubset::new_full(); 5],
             cards_not_seen: DeckSubset::new_full(),
             play_next: Vec::new(),
             partner_play_next: Vec::new(),
+            number_of_players: 2,
         }
     }
 
-    fn all_possible_moves(&self) -> Vec<Move> {
-        let mut all_moves: Vec<Move> = Vec::new();
-        // play and discard moves
-        for i in 0..self.my_hand_knowledge.len() {
-            all_moves.push(Move::Play(i));
-            all_moves.push(Move::Discard(i));
+    // the slot idx left the hand: it leaves the queue and the slots right of it move one to the left
+    fn remove_slot(queue: &mut Vec<usize>, idx: usize) {
+        queue.retain(|&i| i != idx);
+        for i in queue.iter_mut() {
+            if *i > idx { *i -= 1; }
         }
-        // hint moves
-        if self.hints_remaining > 0 {
-            for value in 1..6 {
-                all_moves.push(Move::HintValue(value));
-            }
-            for color in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White] {
-                all_moves.push(Move::HintColor(color));
-            }
-        }
-        all_moves
-    }
-
-    fn exact_card_if_known(&self, idx: usize) -> Option<Card> {
-        // pick the first 1 in the knowledge bitset to get a potential card
-        // then check if this is really the only card in the knowledge
-        let knowledge = &self.my_hand_knowledge[idx];
-        // find position of first 1
-        let first_card_index = knowledge.0.trailing_zeros() as u8;
-        // the cards in the decksubset struct are ordered in the same order as Card takes them, so this index is directly usable
-        let card = Card::new(first_card_index);
-        let card_subset = DeckSubset::from_card_type(&card);
-        knowledge.is_subset(&card_subset).then(|| card)
-
     }
 
     fn playable_cards(&self) -> DeckSubset {
         let mut playable = DeckSubset::new_empty();
-        for (color_index, &top_value) in self.fireworks.iter().enumerate() {
+        for (color_index, &color) in GameVariant::Rainbow.colors().iter().enumerate() {
+            let top_value = self.fireworks[color_index];
             if top_value < 5 {
-                let color = match color_index {
-                    0 => Color::Red,
-                    1 => Color::Green,
-                    2 => Color::Blue,
-                    3 => Color::Yellow,
-                    4 => Color::White,
-                    _ => unreachable!(),
-                };
                 let next_card_subset = DeckSubset::from_color(color)
                     .intersect(&DeckSubset::from_value(top_value + 1));
                 playable = playable.union(&next_card_subset);
@@ -92,16 +74,8 @@ impl Robert2 {
     fn discardable_cards(&self) -> DeckSubset {
         // a card is discardable if fireworks already has it or higher
         let mut discardable = DeckSubset::new_empty();
-        for (color_index, &top_value) in self.fireworks.iter().enumerate() {
-            for value in 1..=top_value {
-                let color = match color_index {
-                    0 => Color::Red,
-                    1 => Color::Green,
-                    2 => Color::Blue,
-                    3 => Color::Yellow,
-                    4 => Color::White,
-                    _ => unreachable!(),
-                };
+        for (color_index, &color) in GameVariant::Rainbow.colors().iter().enumerate() {
+            for value in 1..=self.fireworks[color_index] {
                 let next_card_subset = DeckSubset::from_color(color)
                     .intersect(&DeckSubset::from_value(value));
                 discardable = discardable.union(&next_card_subset);
@@ -110,284 +84,216 @@ impl Robert2 {
         discardable
     }
 
+    // share of the possibilities for a slot, restricted to the cards not seen, that lie in the target set
+    fn probability_in(&self, knowledge: &DeckSubset, target: &DeckSubset) -> f64 {
+        let possibilities = self.cards_not_seen.intersect(knowledge);
+        if possibilities.is_empty() {
+            return 0.0;
+        }
+        possibilities.intersect(target).count() as f64 / possibilities.count() as f64
+    }
+
     // the probability of a card being playable/discardable based on knowledge
     fn probability_playable(&self, idx: usize) -> f64 {
-        // divide number of playable cards in knowledge by total number of cards in knowledge
-        // intersect with cards not seen to only count cards that could still be in hand
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&self.playable_cards())).0.count_ones() as f64 /
-            self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).0.count_ones() as f64
+        self.probability_in(&self.my_hand_knowledge[idx], &self.playable_cards())
     }
     fn probability_discardable(&self, idx: usize) -> f64 {
-        // divide number of discardable cards in knowledge by total number of cards in knowledge
-        // intersect with cards not seen to only count cards that could still be in hand
-        self.cards_not_seen.intersect(&self.my_hand_knowledge[idx].intersect(&&self.discardable_cards())).0.count_ones() as f64 /
-            self.cards_not_seen.intersect(&self.my_hand_knowledge[idx]).0.count_ones() as f64
+        self.probability_in(&self.my_hand_knowledge[idx], &self.discardable_cards())
     }
 
-    // the probability of a card being playable/discardable based on knowledge from partners perspective
+    // the probability of a partner card being playable from the partners perspective,
+    // if we pass a hint, then we want to know the probability after this hint is given
     fn partner_probability_playable(&self, idx: usize, hint: Option<Move>) -> f64 {
-        // if we pass a hint, then we want to know the probability after this hint is given, so we intersect with it
-        let hint_subset = if let Some(h) = hint {
-            match h {
-                Move::HintColor(color) => { DeckSubset::from_color(color) },
-                Move::HintValue(value) => { DeckSubset::from_value(value) },
-                _ => unreachable!()
-            }
-        } else {
-            DeckSubset::new_full()
+        let knowledge = match hint {
+            Some(h) => knowledge_after_hint(&self.partner_hand_knowledge[idx], &h),
+            None => self.partner_hand_knowledge[idx],
         };
-        // divide number of playable cards in knowledge by total number of cards in knowledge
-        // intersect with cards not seen to only count cards that could still be in hand
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&self.playable_cards()))).0.count_ones() as f64 /
-            hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).0.count_ones() as f64
-    }
-
-    fn partner_probability_discardable(&self, idx: usize, hint: Option<Move>) -> f64 {
-        // if we pass a hint, then we want to know the probability after this hint is given, so we intersect with it
-        let hint_subset = if let Some(h) = hint {
-            match h {
-                Move::HintColor(color) => { DeckSubset::from_color(color) },
-                Move::HintValue(value) => { DeckSubset::from_value(value) },
-                _ => unreachable!()
-            }
-        } else {
-            DeckSubset::new_full()
-        };
-        // divide number of discardable cards in knowledge by total number of cards in knowledge
-        // intersect with cards not seen to only count cards that could still be in hand
-        hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx].intersect(&&self.discardable_cards()))).0.count_ones() as f64 /
-            hint_subset.intersect(&self.cards_not_seen.intersect(&self.partner_hand_knowledge[idx])).0.count_ones() as f64
+        self.probability_in(&knowledge, &self.playable_cards())
     }
 
     // the probability of being the only card left of its kind
-    fn probability_only_card_left_of_its_kind(&self, idx: usize) -> f64{
+    fn probability_only_card_left_of_its_kind(&self, idx: usize) -> f64 {
+        let possibilities = self.my_hand_knowledge[idx].intersect(&self.cards_not_seen);
+        if possibilities.is_empty() {
+            return 0.0;
+        }
         let mut number_only_card_left = 0;
-        for value in 0..4 {
-            for color_idx in  0..4 {
-                let card_subset = DeckSubset::from_card_type(&Card::from_value_color_idx(value, color_idx));
-                if card_subset.intersect(&self.my_hand_knowledge[idx]).intersect(&self.cards_not_seen).0.count_ones() == 1 {
-                    number_only_card_left += 1;
-                }
+        for card_type in Card::all_types_for(GameVariant::Rainbow) {
+            if possibilities.remaining_copies(card_type) == 1 {
+                number_only_card_left += 1;
             }
         }
-        number_only_card_left as f64 / self.my_hand_knowledge[idx].intersect(&self.cards_not_seen).0.count_ones() as f64
+        number_only_card_left as f64 / possibilities.count() as f64
     }
 
-    fn number_of_cards_excluded_by_color_hint(&self, color: Color) -> [u8; 5] {
-        let mut number_of_cards_excluded_array = [0u8; 5];
-        for i in 0..self.partner_hand_knowledge.len() {
-            if self.partner_hand[i].get_color() == color {
-                // intersect the subset of all cards that could be in this hand position by the set of cards which do not have this color
-                // this is the number of cards that has been excluded by this hint for this card
-                let number_of_cards_excluded = self.cards_not_seen
-                    .intersect(&self.partner_hand_knowledge[i])
-                    .intersect(&DeckSubset::from_color_inverted(color)).0.count_ones();
-                number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
-            } else {
-                // in this case, the partner learns that this card is not of this color, i.e. all cards of this color are excluded
-                let number_of_cards_excluded = self.cards_not_seen
-                    .intersect(&self.partner_hand_knowledge[i])
-                    .intersect(&DeckSubset::from_color(color)).0.count_ones();
-                number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
-            }
-        }
-        number_of_cards_excluded_array
+    // how many cards the hint rules out over the whole partner hand, touched and untouched slots together
+    fn number_of_cards_excluded_by_hint(&self, hint: &Move, touched: &[usize]) -> u32 {
+        (0..self.partner_hand_knowledge.len()).map(|i| {
+            let before = self.cards_not_seen.intersect(&self.partner_hand_knowledge[i]);
+            let after = if touched.contains(&i) { knowledge_after_hint(&before, hint) } else { knowledge_after_miss(&before, hint) };
+            before.count() - after.count()
+        }).sum()
     }
 
-    fn number_of_cards_excluded_by_value_hint(&self, value: u8) -> [u8; 5] {
-        let mut number_of_cards_excluded_array = [0u8; 5];
-        for i in 0..self.partner_hand_knowledge.len() {
-            if self.partner_hand[i].get_value() == value {
-                // intersect the subset of all cards that could be in this hand position by the set of cards which do not have this value
-                // this is the number of cards that has been excluded by this hint for this card
-                let number_of_cards_excluded = self.cards_not_seen
-                    .intersect(&self.partner_hand_knowledge[i])
-                    .intersect(&&DeckSubset::from_value_inverted(value)).0.count_ones();
-                number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
-            } else {
-                // in this case, the partner learns that this card is not of this value, i.e. all cards of this value are excluded
-                let number_of_cards_excluded = self.cards_not_seen
-                    .intersect(&self.partner_hand_knowledge[i])
-                    .intersect(&&DeckSubset::from_value(value)).0.count_ones();
-                number_of_cards_excluded_array[i] = number_of_cards_excluded as u8;
+    // a hint touching nothing but a playable partner card the partner does not know about yet,
+    // the lowest such card first. Cards of a type already waiting in the partner's queue are skipped,
+    // playing the second copy would be a mistake
+    fn focused_hint_for_partner(&self) -> Option<Move> {
+        let playable = self.playable_cards();
+        let mut candidates: Vec<usize> = (0..self.partner_hand.len())
+            .filter(|&i| playable.has_card(&self.partner_hand[i]))
+            .filter(|i| !self.partner_play_next.contains(i))
+            .filter(|&i| self.partner_probability_playable(i, None) < 1.0)
+            .filter(|&i| !self.partner_play_next.iter().any(|&j| self.partner_hand[j] == self.partner_hand[i]))
+            .collect();
+        candidates.sort_by_key(|&i| self.partner_hand[i].get_value());
+        for i in candidates {
+            let card = self.partner_hand[i];
+            for hint in [hint_touching(&card), Move::HintValue(card.get_value())] {
+                let touched = legal_hints(&self.partner_hand).into_iter().find(|(h, _)| *h == hint).map(|(_, t)| t);
+                if touched.as_deref() == Some(&[i][..]) {
+                    return Some(hint);
+                }
             }
         }
-        number_of_cards_excluded_array
+        None
     }
 }
 
 impl Strategy for Robert2 {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>) {
-        self.partner_hand = other_player_hand.clone();
-        for card in other_player_hand {
+    fn name(&self) -> &'static str { "Robert2" }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.hints_remaining = config.starting_hints;
+        self.max_hints = config.starting_hints;
+        self.max_mistakes = config.max_mistakes;
+        self.number_of_players = other_player_hands.len() + 1;
+        self.cards_not_seen = DeckSubset::new_full_for(config.variant);
+        self.partner_hand = other_player_hands[0].clone();
+        self.my_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        self.partner_hand_knowledge = vec![DeckSubset::new_full(); self.partner_hand.len()];
+        for card in other_player_hands.iter().flatten() {
             self.cards_not_seen.remove_card(card);
         }
     }
 
-    fn decide_move(&mut self) -> Move {
-       unimplemented!()
+    fn reset(&mut self) {
+        *self = Robert2::new();
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+        self.hints_remaining = view.hints_remaining;
+        self.mistakes_made = view.mistakes_made;
+        self.fireworks = view.fireworks;
+        // on the last strike only cards that are certainly playable are played
+        let last_strike = self.mistakes_made + 1 >= self.max_mistakes;
+
+        // 1. the oldest focused hint that can still be playable, the others are dropped
+        while let Some(&idx) = self.play_next.first() {
+            let probability = self.probability_playable(idx);
+            if probability == 1.0 || (probability > 0.0 && !last_strike) {
+                return Move::Play(idx);
+            }
+            self.play_next.remove(0);
+        }
+
+        // 2. a card we know is playable
+        if let Some(idx) = (0..self.my_hand_knowledge.len()).find(|&i| self.probability_playable(i) == 1.0) {
+            return Move::Play(idx);
+        }
+
+        // 3. point the partner at a playable card
+        if self.hints_remaining > 0 && let Some(hint) = self.focused_hint_for_partner() {
+            return hint;
+        }
+
+        // 4. Robert style scoring: the slot most likely to be useless and least likely to be the last copy
+        let best_discard = (0..self.my_hand_knowledge.len())
+            .map(|i| (i, self.probability_discardable(i) - self.probability_only_card_left_of_its_kind(i)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        // a hint that is not read as "play this" and tells the partner the most
+        let best_hint = if self.hints_remaining > 0 {
+            legal_hints(&self.partner_hand).into_iter()
+                .filter(|(_, touched)| touched.len() > 1)
+                .max_by_key(|(hint, touched)| self.number_of_cards_excluded_by_hint(hint, touched))
+                .map(|(hint, _)| hint)
+        } else {
+            None
+        };
+
+        // hints are only given when discarding would waste a hint token or risk the last copy of a card
+        let discard_is_risky = best_discard.is_none_or(|(_, score)| score < 0.0);
+        if let Some(hint) = best_hint && (self.hints_remaining == self.max_hints || discard_is_risky) {
+            return hint;
+        }
+        match best_discard {
+            Some((idx, _)) => Move::Discard(idx),
+            None => safe_fallback_move(self.my_hand_knowledge.len(), &self.partner_hand, self.hints_remaining, view.discard_allowed),
+        }
     }
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
         match mv {
-            Move::Play(idx) => {
-                match mv_result {
-                    MoveResult::Play(success, card_played, _) => {
-                        if *success {
-                            // Update fireworks
-                            let color_index = card_played.get_color() as usize;
-                            self.fireworks[color_index] += 1;
-                        } else {
-                            self.mistakes_made += 1;
-                        }
-                        // Remove played card knowledge
-                        self.my_hand_knowledge.remove(*idx);
-                        if got_new_card {
-                            self.my_hand_knowledge.push(DeckSubset::new_full());
-                        }
-                    },
-                    _ => ()
-                }
-                // if we played the focused hint, then its None now
-                if let Some(i) = self.focused_hint && i == *idx {
-                    self.focused_hint = None;
-                }
-                // if we played a card left of the focused hint, then we must shift it
-                if let Some(i) = self.focused_hint && i > *idx {
-                    self.focused_hint = Some(i-1);
-                }
-            }
-            Move::Discard(idx) => {
-                // Remove discarded card knowledge
-                self.my_hand_knowledge.remove(*idx);
-                if got_new_card {
-                    self.my_hand_knowledge.push(DeckSubset::new_full());
-                }
-                if self.hints_remaining < 8 {
-                    self.hints_remaining += 1;
-                }
-                // if we discarded the focused hint, then its None now
-                if let Some(i) = self.focused_hint && i == *idx {
-                    self.focused_hint = None;
-                }
-                // if we discarded a card left of the focused hint, then we must shift it
-                if let Some(i) = self.focused_hint && i > *idx {
-                    self.focused_hint = Some(i-1);
-                }
-            }
-            Move::HintColor(color) => {
-                self.hints_remaining -= 1;
-                // Update partner's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
-                        }
-                    },
-                    _ => ()
+            Move::Play(idx) | Move::Discard(idx) => {
+                if let Some((card, on_discard_pile)) = apply_own_card_move(&mut self.my_hand_knowledge, *idx, got_new_card.then(DeckSubset::new_full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                    self.cards_not_seen.remove_card(&card);
+                    if on_discard_pile && matches!(mv, Move::Play(_)) {
+                        self.mistakes_made += 1;
+                    }
                 }
+                // the played or discarded card leaves the queue, the cards right of it shift
+                Self::remove_slot(&mut self.play_next, *idx);
             }
-            Move::HintValue(value) => {
-                self.hints_remaining -= 1;
+            Move::HintColor(_) | Move::HintValue(_) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
                 // Update partner's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        for i in indices.iter() {
-                            self.partner_hand_knowledge[*i] = self.partner_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
-                        }
-                    },
-                    _ => ()
+                if let MoveResult::Hint { indices } = mv_result {
+                    apply_hint(&mut self.partner_hand_knowledge, indices, mv);
+                    // if the hint is only about one card, then it is a focused hint
+                    if indices.len() == 1 && !self.partner_play_next.contains(&indices[0]) {
+                        self.partner_play_next.push(indices[0]);
+                    }
                 }
             }
         }
     }
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult) {
-        match mv {
-            Move::Play(idx) => {
-                match mv_result {
-                    MoveResult::Play(success, card_played, card_drawn) => {
-                        self.cards_not_seen.remove_card(card_played); // both see this card
-                        if *success {
-                            // Update fireworks
-                            let color_index = card_played.get_color() as usize;
-                            self.fireworks[color_index] += 1;
-                        } else {
-                            self.mistakes_made += 1;
-                        }
-                        // Remove played card knowledge and hand and add new card if drawn
-                        self.partner_hand_knowledge.remove(*idx);
-                        self.partner_hand.remove(*idx);
-                        if let Some(card) = card_drawn {
-                            self.partner_hand.push(*card);
-                            self.partner_hand_knowledge.push(DeckSubset::new_full());
-                            self.cards_not_seen.remove_card(card);
-                        }
-                    },
-                    _ => ()
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        let concerns_us = concerns_partner_or_me(player_offset, self.number_of_players, mv);
+        match (mv, mv_result) {
+            (Move::Play(idx) | Move::Discard(idx), MoveResult::Play { card, drawn, .. } | MoveResult::Discard { card, drawn }) => {
+                if let Some((_, true)) = apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints)
+                    && matches!(mv, Move::Play(_)) {
+                    self.mistakes_made += 1;
                 }
-            }
-            Move::Discard(idx) => {
-                match mv_result {
-                    MoveResult::Discard(card_discarded, card_drawn) => {
-                        self.cards_not_seen.remove_card(card_discarded); // both see this card
-                        if self.hints_remaining < 8 {
-                            self.hints_remaining += 1;
-                        }
-                        // Remove played card knowledge and hand and add new card if drawn
-                        self.partner_hand_knowledge.remove(*idx);
-                        self.partner_hand.remove(*idx);
-                        if let Some(card) = card_drawn {
-                            self.partner_hand.push(*card);
-                            self.partner_hand_knowledge.push(DeckSubset::new_full());
-                            self.cards_not_seen.remove_card(card);
-                        }
-                    },
-                    _ => ()
+                self.cards_not_seen.remove_card(card); // both see this card
+                if let Some(new_card) = drawn { self.cards_not_seen.remove_card(new_card); }
+                // plays and discards between other players only change the board
+                if concerns_us {
+                    // Remove played card knowledge and hand and add new card if drawn
+                    self.partner_hand_knowledge.remove(*idx);
+                    self.partner_hand.remove(*idx);
+                    Self::remove_slot(&mut self.partner_play_next, *idx);
+                    if let Some(new_card) = drawn {
+                        self.partner_hand.push(*new_card);
+                        self.partner_hand_knowledge.push(DeckSubset::new_full());
+                    }
                 }
             }
-            Move::HintColor(color) => {
-                self.hints_remaining -= 1;
-                // Update own's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        // update the cards the hint was about
-                        for i in indices.iter() {
-                            self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_color(*color));
-                        }
-                        // update the other cards
-                        for i in (0..self.my_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
-                            self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_color_inverted(*color));
-                        }
-                        // if the hint is only about one card, then it is a focused hint
-                        if indices.len() == 1 {
-                            self.focused_hint = Some(indices[0]);
-                        }
-                    },
-                    _ => ()
-                }
-            }
-            Move::HintValue(value) => {
-                self.hints_remaining -= 1;
-                // Update own's hand knowledge based on hint
-                match mv_result {
-                    MoveResult::Hint(indices) => {
-                        // update the cards the hint was about
-                        for i in indices.iter() {
-                            self.my_hand_knowledge[*i] = self.my_hand_knowledge[*i].intersect(&DeckSubset::from_value(*value));
-                        }
-                        // update the other cards
-                        for i in (0..self.my_hand_knowledge.len()).filter(|x| !indices.contains(x)) {
-                            self.my_hand_knowledge[i] = self.my_hand_knowledge[i].intersect(&DeckSubset::from_value_inverted(*value));
-                        }
-                        // if the hint is only about one card, then it is a focused hint
-                        if indices.len() == 1 {
-                            self.focused_hint = Some(indices[0]);
-                        }
-                    },
-                    _ => ()
+            (Move::HintColor(_) | Move::HintValue(_), MoveResult::Hint { indices }) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
+                if concerns_us {
+                    // Update own's hand knowledge based on hint
+                    apply_hint(&mut self.my_hand_knowledge, indices, mv);
+                    // if the hint is only about one card, then it is a focused hint
+                    if indices.len() == 1 && !self.play_next.contains(&indices[0]) {
+                        self.play_next.push(indices[0]);
+                    }
                 }
             }
+            _ => (),
         }
     }
-}
\ No newline at end of file
+}
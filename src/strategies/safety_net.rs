@@ -0,0 +1,131 @@
+use crate::enums::{Move, MoveResult};
+use crate::card::Card;
+use crate::strategy::Strategy;
+use crate::strategy_utils::{hint_touching, wrapper_name, BoardTracker};
+use crate::game::{GameConfig, GameView};
+
+
+/// Wraps any strategy and blocks plays that could lose the game.
+///
+/// All callbacks are forwarded to the inner strategy. On the last strike a play of a card
+/// that is not certainly playable is replaced by a hint (or a discard of that card if no hint is left).
+pub struct SafetyNet {
+    inner: Box<dyn Strategy>,
+    board: BoardTracker,
+}
+
+impl SafetyNet {
+    pub fn new(inner: Box<dyn Strategy>) -> Self {
+        SafetyNet { inner, board: BoardTracker::new() }
+    }
+
+    fn is_certainly_playable(&self, idx: usize) -> bool {
+        let possibilities = self.board.possibilities(idx);
+        !possibilities.is_empty() && possibilities.iter_cards()
+            .all(|card| self.board.fireworks[card.get_color().index()] + 1 == card.get_value())
+    }
+}
+
+impl Strategy for SafetyNet {
+    // the registered wrappers get their registry name, any other wrapped strategy the generic one
+    fn name(&self) -> &'static str {
+        wrapper_name(self.inner.name(), &[("Gemini", "SafeGemini"), ("ChatGPT", "SafeChatGPT")], "SafetyNet")
+    }
+
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        self.board.initialize(other_player_hands, config);
+        self.inner.initialize(other_player_hands, config);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.board = BoardTracker::new();
+    }
+
+    fn negotiate(&mut self, partner_name: &str) {
+        self.board.negotiate(self.name(), partner_name);
+        self.inner.negotiate(partner_name);
+    }
+
+    fn decide_move(&mut self, view: &GameView) -> Move {
+        self.board.update_from_view(view);
+        let mv = self.inner.decide_move(view);
+        if let Move::Play(idx) = mv
+            && self.board.mistakes_made + 1 >= self.board.max_mistakes
+            && idx < view.hand_size(0)
+            && !self.is_certainly_playable(idx) {
+            if self.board.hints_remaining > 0 && let Some(card) = self.board.partner_hand.first() {
+                return hint_touching(card);
+            }
+            return Move::Discard(idx);
+        }
+        mv
+    }
+
+    fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
+        self.board.update_after_own_move(mv, mv_result, got_new_card);
+        self.inner.update_after_own_move(mv, mv_result, got_new_card);
+    }
+
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        self.board.update_after_other_player_move(player_offset, mv, mv_result);
+        self.inner.update_after_other_player_move(player_offset, mv, mv_result);
+    }
+
+    fn see(&mut self, card: &Card) {
+        self.inner.see(card);
+    }
+
+    fn seed_rng(&mut self, seed: u64) {
+        self.inner.seed_rng(seed);
+    }
+
+    fn on_game_over(&mut self, final_score: u8) {
+        self.inner.on_game_over(final_score);
+    }
+
+    fn play_probability(&self, idx: usize) -> Option<f64> {
+        self.inner.play_probability(idx)
+    }
+
+    fn game_log(&self) -> Option<String> {
+        self.inner.game_log()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::Color;
+    use crate::game::Game;
+    use crate::player::Player;
+
+    // plays its oldest card every turn, whatever it is
+    struct AlwaysPlay;
+
+    impl Strategy for AlwaysPlay {
+        fn name(&self) -> &'static str { "AlwaysPlay" }
+        fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+        fn reset(&mut self) {}
+        fn decide_move(&mut self, _view: &GameView) -> Move { Move::Play(0) }
+        fn update_after_own_move(&mut self, _mv: &Move, _mv_result: &MoveResult, _got_new_card: bool) {}
+        fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, _mv_result: &MoveResult) {}
+    }
+
+    #[test]
+    fn risky_play_on_the_last_strike_becomes_a_hint() {
+        let players = vec![Player::new(Box::new(SafetyNet::new(Box::new(AlwaysPlay)))), Player::new(Box::new(AlwaysPlay))];
+        let hands = vec![vec![Card::new(3), Card::new(5), Card::new(13), Card::new(15), Card::new(23)],
+                         vec![Card::new(33), Card::new(35), Card::new(43), Card::new(45), Card::new(25)]];
+        let mut game = Game::new_with_hands(players, hands);
+        game.deck.cards = vec![Card::new(40), Card::new(41), Card::new(42)];
+        assert_eq!(game.decide_move(), Move::Play(0));
+
+        // both players misplay a 2, one strike is left
+        game.apply_move(Move::Play(0)).unwrap();
+        game.apply_move(Move::Play(0)).unwrap();
+        assert_eq!(game.mistakes_made(), 2);
+        // the red 3 in slot 0 could be anything to the player, so the play is turned into a hint
+        assert_eq!(game.decide_move(), Move::HintColor(Color::Yellow));
+    }
+}
@@ -1,12 +1,55 @@
 use crate::card::Card;
 use crate::enums::*;
+use crate::game::{GameConfig, GameView, FullGameState};
 
 pub trait Strategy {
-    fn initialize(&mut self, other_player_hand: &Vec<Card>);
+    // the name results are reported with, the same as in the registry in main.rs
+    fn name(&self) -> &'static str;
 
-    fn decide_move(&mut self) -> Move;
+    // the hands of all other players in turn order, starting with the next player,
+    // and the rules of the game, like the number of hint tokens
+    fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig);
+
+    // forgets everything about the last game, so one instance can play the next one. Settings chosen
+    // at construction (params, conventions, a wrapped strategy) are kept, everything else is as after new
+    fn reset(&mut self);
+
+    // called at the start of every game, before initialize, with the name of the next player, the one
+    // our hints go to (with two players the partner). A strategy that knows how that partner hints can
+    // agree on the conventions both play. Strategies that trust every partner alike can ignore this
+    fn negotiate(&mut self, _partner_name: &str) {}
+
+    // the view is the public state of the game as the engine sees it. Strategies should take their
+    // counters (hints, strikes, fireworks) from it instead of trusting their own bookkeeping
+    fn decide_move(&mut self, view: &GameView) -> Move;
 
     fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool);
 
-    fn update_after_other_player_move(&mut self, mv: &Move, mv_result: &MoveResult);
+    // player_offset is how many seats after us the moving player sits, 1 is the next player.
+    // hints always go to the player after the one giving them, so a hint from the previous player
+    // (offset = number of players - 1) is about our own hand. With two players both are the partner
+    fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult);
+
+    // called when a card enters another player's hand, right after the draw and before
+    // update_after_other_player_move for the same move. Our own draws are never shown.
+    // The drawn card is also in the move result, so strategies can ignore this
+    fn see(&mut self, _card: &Card) {}
+
+    // called right before decide_move with every card of the game, our own hand and the deck included.
+    // only the Cheater uses this to measure the ceiling of perfect information play, everyone else must ignore it
+    fn observe_full_state(&mut self, _state: &FullGameState) {}
+
+    // reseeds any internal randomness of the strategy. Deterministic strategies can ignore this
+    fn seed_rng(&mut self, _seed: u64) {}
+
+    // called once when the game has ended, with the score it ended with (0 for a lost game)
+    fn on_game_over(&mut self, _final_score: u8) {}
+
+    // how likely the strategy thinks the card in slot idx of its own hand is playable,
+    // None if the strategy does not reason in probabilities
+    fn play_probability(&self, _idx: usize) -> Option<f64> { None }
+
+    // a readable log of the game the strategy kept, printed after a single game. None for strategies
+    // that keep no log, which is all of them except the Observer
+    fn game_log(&self) -> Option<String> { None }
 }
\ No newline at end of file
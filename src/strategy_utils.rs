@@ -0,0 +1,464 @@
+use crate::card::Card;
+use crate::decksubset::DeckSubset;
+use crate::enums::{Move, MoveResult, Color};
+use crate::game::{GameConfig, GameView};
+use crate::conventions::{plays_conventions, read_hint};
+
+// Helpers shared by the strategies that track their hand as one DeckSubset per slot
+
+// the card type of a slot if its knowledge, restricted to the cards still unseen, allows only one type
+pub fn resolved_card(knowledge: &DeckSubset, unseen: &DeckSubset) -> Option<Card> {
+    let possibilities = knowledge.intersect(unseen);
+    if possibilities.is_empty() {
+        return None;
+    }
+    // the cards in the decksubset struct are ordered in the same order as Card takes them, so this index is directly usable
+    let card = Card::new(possibilities.0.trailing_zeros() as u8);
+    possibilities.is_subset(&DeckSubset::from_card_type(&card)).then_some(card)
+}
+
+// for every slot Some(card) if the slot is fully resolved, None otherwise
+pub fn resolved_hand(knowledge: &[DeckSubset], unseen: &DeckSubset) -> Vec<Option<Card>> {
+    knowledge.iter().map(|k| resolved_card(k, unseen)).collect()
+}
+
+// true if slot idx is known exactly and another copy of that kind is held as well, in another known
+// slot of ours or in the partner's hand. discarding it loses nothing as long as the other copy is kept
+pub fn is_known_duplicate(resolved: &[Option<Card>], idx: usize, partner_hand: &[Card]) -> bool {
+    let Some(card) = resolved[idx] else { return false };
+    resolved.iter().enumerate().any(|(i, other)| i != idx && other.is_some_and(|other| other.same_kind(&card)))
+        || partner_hand.iter().any(|other| other.same_kind(&card))
+}
+
+// a move the engine accepts, for when a strategy has nothing better (e.g. deep in the endgame):
+// discard our first card if we hold one and may discard, otherwise hint the color of a partner card,
+// and if neither is possible play our first card, the only legal move left. with an empty hand and no
+// hint to give there is no legal move at all, the Discard(0) returned then is refused as IndexOutOfBounds
+pub fn safe_fallback_move(own_hand_size: usize, partner_hand: &[Card], hints_remaining: u8, discard_allowed: bool) -> Move {
+    if own_hand_size > 0 && discard_allowed {
+        return Move::Discard(0);
+    }
+    if hints_remaining > 0 && let Some(card) = partner_hand.first() {
+        return hint_touching(card);
+    }
+    if own_hand_size > 0 {
+        return Move::Play(0);
+    }
+    Move::Discard(0)
+}
+
+// true if a hint touched the slot: every possibility left shares one color (rainbow aside) or one value.
+// a hint that misses a slot only rules a color or value out, so an untouched slot keeps several of both,
+// unless the misses ruled out all but one, which tells the slot as much as a touching hint would
+pub fn is_touched(knowledge: &DeckSubset) -> bool {
+    let rainbow = DeckSubset::from_color(Color::Rainbow);
+    [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White].into_iter()
+        .any(|color| knowledge.is_subset(&DeckSubset::from_color(color).union(&rainbow)))
+        || (1..=5).any(|value| knowledge.is_subset(&DeckSubset::from_value(value)))
+}
+
+// the chop of a hand: the oldest slot no hint has touched yet, the card a player discards first.
+// new cards are added at the end, so that is the lowest such index. None if every slot was hinted
+pub fn chop_index(knowledge: &[DeckSubset]) -> Option<usize> {
+    knowledge.iter().position(|k| !is_touched(k))
+}
+
+// a hint that touches this card: its color, or its value for a rainbow card since rainbow can't be hinted
+pub fn hint_touching(card: &Card) -> Move {
+    if card.get_color() == Color::Rainbow {
+        Move::HintValue(card.get_value())
+    } else {
+        Move::HintColor(card.get_color())
+    }
+}
+
+// bookkeeping after our own play or discard: drops the knowledge of the slot the card left,
+// adds new_slot, what a fresh card can be in this variant, if a card was drawn and updates fireworks and hints.
+// returns the card that left the hand and whether it ended up on the discard pile (discarded or misplayed)
+pub fn apply_own_card_move(knowledge: &mut Vec<DeckSubset>, idx: usize, new_slot: Option<DeckSubset>, mv_result: &MoveResult, fireworks: &mut [u8; 6], hints_remaining: &mut u8, max_hints: u8) -> Option<(Card, bool)> {
+    if idx < knowledge.len() { knowledge.remove(idx); }
+    if let Some(new_slot) = new_slot { knowledge.push(new_slot); }
+    apply_card_result(mv_result, fireworks, hints_remaining, max_hints)
+}
+
+// updates fireworks and hints for the result of anyone's play or discard, a discard or a completed stack
+// regains a hint up to max_hints. returns the card and whether it ended up on the discard pile
+pub fn apply_card_result(mv_result: &MoveResult, fireworks: &mut [u8; 6], hints_remaining: &mut u8, max_hints: u8) -> Option<(Card, bool)> {
+    match mv_result {
+        MoveResult::Play { success, card, .. } => {
+            if *success {
+                fireworks[card.get_color().index()] += 1;
+                if card.get_value() == 5 && *hints_remaining < max_hints { *hints_remaining += 1; }
+            }
+            Some((*card, !*success))
+        }
+        MoveResult::Discard { card, .. } => {
+            if *hints_remaining < max_hints { *hints_remaining += 1; }
+            Some((*card, true))
+        }
+        MoveResult::Hint { .. } => None,
+    }
+}
+
+// true for the moves of other players that the two player bookkeeping of a strategy is written for:
+// plays and discards of the next player, whose hand is tracked as the partner hand,
+// and hints from the previous player, which are about our own hand. always true with two players
+pub fn concerns_partner_or_me(player_offset: usize, number_of_players: usize, mv: &Move) -> bool {
+    match mv {
+        Move::Play(_) | Move::Discard(_) => player_offset == 1,
+        Move::HintColor(_) | Move::HintValue(_) => player_offset + 1 == number_of_players,
+    }
+}
+
+// every color and value hint that touches at least one card of the partner, with the touched indices
+pub fn legal_hints(partner_hand: &[Card]) -> Vec<(Move, Vec<usize>)> {
+    let mut hints = Vec::new();
+    for color in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White] {
+        let indices: Vec<usize> = (0..partner_hand.len()).filter(|&i| partner_hand[i].get_color().touched_by(color)).collect();
+        if !indices.is_empty() { hints.push((Move::HintColor(color), indices)); }
+    }
+    for value in 1..=5 {
+        let indices: Vec<usize> = (0..partner_hand.len()).filter(|&i| partner_hand[i].get_value() == value).collect();
+        if !indices.is_empty() { hints.push((Move::HintValue(value), indices)); }
+    }
+    hints
+}
+
+// the knowledge a slot has after a hint touched it
+pub fn knowledge_after_hint(knowledge: &DeckSubset, hint: &Move) -> DeckSubset {
+    match hint {
+        Move::HintColor(color) => knowledge.intersect(&DeckSubset::from_color(*color).union(&DeckSubset::from_color(Color::Rainbow))),
+        Move::HintValue(value) => knowledge.intersect(&DeckSubset::from_value(*value)),
+        _ => *knowledge,
+    }
+}
+
+// the knowledge a slot has after a hint did not touch it: a color hint also rules out rainbow
+pub fn knowledge_after_miss(knowledge: &DeckSubset, hint: &Move) -> DeckSubset {
+    match hint {
+        Move::HintColor(color) => knowledge.intersect(&DeckSubset::from_color_inverted(*color)).intersect(&DeckSubset::from_color_inverted(Color::Rainbow)),
+        Move::HintValue(value) => knowledge.intersect(&DeckSubset::from_value_inverted(*value)),
+        _ => *knowledge,
+    }
+}
+
+// narrows the knowledge of a whole hand after a hint touched the cards at indices,
+// the untouched cards, all of them for an empty hint, are narrowed too
+pub fn apply_hint(knowledge: &mut [DeckSubset], indices: &[usize], hint: &Move) {
+    for (i, k) in knowledge.iter_mut().enumerate() {
+        *k = if indices.contains(&i) { knowledge_after_hint(k, hint) } else { knowledge_after_miss(k, hint) };
+    }
+}
+
+// the hint touching the target slot of the partner that brings it closest to being known as playable:
+// first hints after which every possibility is playable, then the higher share of playable possibilities,
+// then the hint touching fewer other cards. None if neither the color nor the value hint narrows the slot
+pub fn best_hint_for_slot(partner_hand: &[Card], partner_knowledge: &[DeckSubset], target: usize, fireworks: &[u8; 6], unseen: &DeckSubset) -> Option<Move> {
+    let mut playable = DeckSubset::new_empty();
+    for (color_index, color) in [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White].into_iter().enumerate() {
+        if fireworks[color_index] < 5 {
+            playable = playable.union(&DeckSubset::from_color(color).intersect(&DeckSubset::from_value(fireworks[color_index] + 1)));
+        }
+    }
+
+    let card = partner_hand[target];
+    let before = partner_knowledge[target].intersect(unseen);
+    let mut best: Option<(Move, bool, f64, usize)> = None;
+    for hint in [hint_touching(&card), Move::HintValue(card.get_value())] {
+        let after = knowledge_after_hint(&before, &hint);
+        if after == before { continue; }
+        let certain = after.is_subset(&playable);
+        let share = after.intersect(&playable).count() as f64 / after.count() as f64;
+        let touched = partner_hand.iter().filter(|c| match hint {
+            Move::HintColor(color) => c.get_color().touched_by(color),
+            _ => c.get_value() == card.get_value(),
+        }).count();
+        let better = match best {
+            None => true,
+            Some((_, best_certain, best_share, best_touched)) =>
+                (certain, share, std::cmp::Reverse(touched)) > (best_certain, best_share, std::cmp::Reverse(best_touched)),
+        };
+        if better {
+            best = Some((hint, certain, share, touched));
+        }
+    }
+    best.map(|(hint, _, _, _)| hint)
+}
+
+// the board, the partner's hand and what the hints told us about our own hand, followed from the moves alone.
+// for the strategies that wrap another one and check its moves (SafetyNet, OnePlyLookahead), they
+// forward every callback to the inner strategy and keep this next to it
+pub struct BoardTracker {
+    pub hints_remaining: u8,
+    pub max_hints: u8, // the starting hint tokens, a discard never regains more
+    pub mistakes_made: u8,
+    pub max_mistakes: u8, // the game is lost on this many mistakes
+    pub fireworks: [u8; 6],
+    pub my_hand_knowledge: Vec<DeckSubset>,
+    pub partner_hand: Vec<Card>,
+    pub cards_not_seen: DeckSubset,
+    full: DeckSubset, // every card of the variant, what a freshly drawn card can be
+    number_of_players: usize,
+    conventions: bool, // we and the partner read hints through the shared conventions (see negotiate)
+}
+
+impl Default for BoardTracker {
+    fn default() -> Self {
+        BoardTracker::new()
+    }
+}
+
+impl BoardTracker {
+    // the state before any game, initialize sets it up from the game's config
+    pub fn new() -> Self {
+        let config = GameConfig::for_players(2);
+        BoardTracker {
+            hints_remaining: config.starting_hints,
+            max_hints: config.starting_hints,
+            mistakes_made: 0,
+            max_mistakes: config.max_mistakes,
+            fireworks: [0; 6],
+            my_hand_knowledge: Vec::new(),
+            partner_hand: Vec::new(),
+            cards_not_seen: DeckSubset::new_full_for(config.variant),
+            full: DeckSubset::new_full_for(config.variant),
+            number_of_players: 2,
+            conventions: false,
+        }
+    }
+
+    pub fn initialize(&mut self, other_player_hands: &[Vec<Card>], config: &GameConfig) {
+        let conventions = self.conventions;
+        *self = BoardTracker {
+            hints_remaining: config.starting_hints,
+            max_hints: config.starting_hints,
+            max_mistakes: config.max_mistakes,
+            my_hand_knowledge: vec![DeckSubset::new_full_for(config.variant); other_player_hands[0].len()],
+            partner_hand: other_player_hands[0].clone(),
+            cards_not_seen: DeckSubset::new_full_for(config.variant),
+            full: DeckSubset::new_full_for(config.variant),
+            number_of_players: other_player_hands.len() + 1,
+            conventions,
+            ..BoardTracker::new()
+        };
+        for card in other_player_hands.iter().flatten() {
+            self.cards_not_seen.remove_card(card);
+        }
+    }
+
+    // own_name is the wrapper's name, hints are read through the conventions only if both of us play them
+    pub fn negotiate(&mut self, own_name: &str, partner_name: &str) {
+        self.conventions = plays_conventions(own_name) && plays_conventions(partner_name);
+    }
+
+    // the engine's counters are the truth, our own bookkeeping only tracks knowledge
+    pub fn update_from_view(&mut self, view: &GameView) {
+        self.hints_remaining = view.hints_remaining;
+        self.mistakes_made = view.mistakes_made;
+        self.fireworks = view.fireworks;
+    }
+
+    // the unseen cards the card in slot idx can still be
+    pub fn possibilities(&self, idx: usize) -> DeckSubset {
+        self.my_hand_knowledge[idx].intersect(&self.cards_not_seen)
+    }
+
+    pub fn update_after_own_move(&mut self, mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
+        if let Move::Play(idx) | Move::Discard(idx) = *mv {
+            if let Some((card, to_discard_pile)) = apply_own_card_move(&mut self.my_hand_knowledge, idx, got_new_card.then_some(self.full), mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints) {
+                if to_discard_pile && matches!(mv, Move::Play(_)) { self.mistakes_made += 1; }
+                self.cards_not_seen.remove_card(&card);
+            }
+        } else {
+            self.hints_remaining = self.hints_remaining.saturating_sub(1);
+        }
+    }
+
+    pub fn update_after_other_player_move(&mut self, player_offset: usize, mv: &Move, mv_result: &MoveResult) {
+        let concerns_us = concerns_partner_or_me(player_offset, self.number_of_players, mv);
+        match (mv, mv_result) {
+            (Move::Play(idx) | Move::Discard(idx), MoveResult::Play { drawn, .. } | MoveResult::Discard { drawn, .. }) => {
+                if let Some((_, true)) = apply_card_result(mv_result, &mut self.fireworks, &mut self.hints_remaining, self.max_hints)
+                    && matches!(mv, Move::Play(_)) {
+                    self.mistakes_made += 1;
+                }
+                // the partner's cards were removed from the unseen cards when we first saw them
+                if concerns_us && *idx < self.partner_hand.len() {
+                    self.partner_hand.remove(*idx);
+                    if let Some(new_card) = drawn { self.partner_hand.push(*new_card); }
+                }
+                if let Some(new_card) = drawn { self.cards_not_seen.remove_card(new_card); }
+            }
+            (_, MoveResult::Hint { indices }) => {
+                self.hints_remaining = self.hints_remaining.saturating_sub(1);
+                if concerns_us { self.my_hand_knowledge = read_hint(self.conventions, mv, &self.my_hand_knowledge, indices, &self.fireworks); }
+            }
+            _ => (),
+        }
+    }
+}
+
+// the name of a wrapper around the inner strategy: the registered name if the wrapper is registered
+// with it, given as (inner name, wrapper name) pairs, the generic name otherwise
+pub fn wrapper_name(inner_name: &str, registered: &[(&str, &'static str)], generic: &'static str) -> &'static str {
+    registered.iter().find(|(inner, _)| *inner == inner_name).map_or(generic, |(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::GameVariant;
+
+    #[test]
+    fn chop_is_the_oldest_untouched_slot() {
+        let mut knowledge = vec![DeckSubset::new_full(); 5];
+        assert_eq!(chop_index(&knowledge), Some(0));
+        // a red hint touching slots 0 and 2 narrows the other slots too, they stay untouched
+        apply_hint(&mut knowledge, &[0, 2], &Move::HintColor(Color::Red));
+        assert_eq!(chop_index(&knowledge), Some(1));
+        apply_hint(&mut knowledge, &[1, 3], &Move::HintValue(1));
+        assert_eq!(chop_index(&knowledge), Some(4));
+    }
+
+    #[test]
+    fn all_hinted_hand_has_no_chop() {
+        let mut knowledge = vec![DeckSubset::new_full(); 5];
+        apply_hint(&mut knowledge, &[0, 1, 2, 3, 4], &Move::HintValue(2));
+        assert_eq!(chop_index(&knowledge), None);
+        assert_eq!(chop_index(&[]), None);
+    }
+
+    #[test]
+    fn fallback_hints_instead_of_a_forbidden_discard() {
+        let partner_hand = [Card::new(10), Card::new(20)];
+        assert_eq!(safe_fallback_move(5, &partner_hand, 8, true), Move::Discard(0));
+        assert_eq!(safe_fallback_move(5, &partner_hand, 8, false), Move::HintColor(Color::Green));
+        // nothing to hint either, so only a play is left
+        assert_eq!(safe_fallback_move(5, &[], 8, false), Move::Play(0));
+    }
+
+    // a hint that touched no card still tells the hand that none of its cards has the value
+    #[test]
+    fn empty_value_hint_narrows_every_slot() {
+        let mut knowledge = vec![DeckSubset::new_full(); 5];
+        apply_hint(&mut knowledge, &[], &Move::HintValue(5));
+        let no_five = DeckSubset::new_full().intersect(&DeckSubset::from_value_inverted(5));
+        assert!(knowledge.iter().all(|k| *k == no_five));
+        assert!(!knowledge[0].has_card(&Card::new(9)));
+        assert!(knowledge[0].has_card(&Card::new(7)));
+    }
+
+    // on an empty board every 1 is playable, so the value hint makes the red 1 certain and the color hint does not
+    #[test]
+    fn value_hint_is_best_for_a_playable_one() {
+        let partner_hand = [Card::new(0), Card::new(13), Card::new(25), Card::new(37), Card::new(3)];
+        let mut knowledge = vec![DeckSubset::new_full(); 5];
+        let unseen = DeckSubset::new_full();
+        assert_eq!(best_hint_for_slot(&partner_hand, &knowledge, 0, &[0; 6], &unseen), Some(Move::HintValue(1)));
+        // once both hints were given neither narrows the slot any further
+        apply_hint(&mut knowledge, &[0], &Move::HintValue(1));
+        apply_hint(&mut knowledge, &[0, 4], &Move::HintColor(Color::Red));
+        assert_eq!(best_hint_for_slot(&partner_hand, &knowledge, 0, &[0; 6], &unseen), None);
+    }
+
+    // with every red 2 to 5 gone both hints make the red 1 certain, the color hint wins as the value hint
+    // also touches the green 1
+    #[test]
+    fn color_hint_is_best_when_another_one_is_held() {
+        let partner_hand = [Card::new(0), Card::new(10), Card::new(25), Card::new(37), Card::new(16)];
+        let knowledge = vec![DeckSubset::new_full(); 5];
+        let mut unseen = DeckSubset::new_full();
+        for code in 3..10 {
+            unseen.remove_card(&Card::new(code));
+        }
+        assert_eq!(best_hint_for_slot(&partner_hand, &knowledge, 0, &[0; 6], &unseen), Some(Move::HintColor(Color::Red)));
+        assert_eq!(best_hint_for_slot(&partner_hand, &knowledge, 0, &[0; 6], &DeckSubset::new_full()), Some(Move::HintValue(1)));
+    }
+
+    // the helper and the board tracker built on it end up in the same state, for a play that drew and a discard that didn't.
+    // in the rainbow variant the drawn card can be a rainbow card too
+    #[test]
+    fn own_card_moves_update_the_board_alike() {
+        for variant in [GameVariant::Standard, GameVariant::Rainbow] {
+            let full = DeckSubset::new_full_for(variant);
+            let mut start = vec![full; 5];
+            apply_hint(&mut start, &[1], &Move::HintValue(1));
+            let moves = [
+                (Move::Play(1), MoveResult::Play { success: true, card: Card::new(0), drawn: None }, true),
+                (Move::Discard(1), MoveResult::Discard { card: Card::new(0), drawn: None }, false),
+            ];
+            for (mv, result, got_new_card) in moves {
+                let mut knowledge = start.clone();
+                let (mut fireworks, mut hints) = ([0; 6], 7);
+                assert_eq!(apply_own_card_move(&mut knowledge, 1, got_new_card.then_some(full), &result, &mut fireworks, &mut hints, 8), Some((Card::new(0), matches!(mv, Move::Discard(_)))));
+
+                let mut expected = start.clone();
+                expected.remove(1);
+                if got_new_card { expected.push(full); }
+                assert_eq!(knowledge, expected);
+
+                let mut board = BoardTracker::new();
+                board.initialize(&[(10..15).map(Card::new).collect()], &GameConfig { variant, ..GameConfig::for_players(2) });
+                board.my_hand_knowledge = start.clone();
+                board.hints_remaining = 7;
+                board.update_after_own_move(&mv, &result, got_new_card);
+                assert_eq!((board.my_hand_knowledge, board.fireworks, board.hints_remaining), (knowledge, fireworks, hints), "{:?}", variant);
+            }
+        }
+    }
+
+    // a rainbow card is touched by every color hint. without it there is no hint for the blue, yellow
+    // and white the hand lacks, and never one for its missing 4 and 5
+    #[test]
+    fn legal_hints_touch_at_least_one_card() {
+        let partner_hand = [Card::new(0), Card::new(1), Card::new(13), Card::new(5), Card::new(55)];
+        let expected = vec![
+            (Move::HintColor(Color::Red), vec![0, 1, 3, 4]),
+            (Move::HintColor(Color::Green), vec![2, 4]),
+            (Move::HintColor(Color::Blue), vec![4]),
+            (Move::HintColor(Color::Yellow), vec![4]),
+            (Move::HintColor(Color::White), vec![4]),
+            (Move::HintValue(1), vec![0, 1]),
+            (Move::HintValue(2), vec![2]),
+            (Move::HintValue(3), vec![3, 4]),
+        ];
+        assert_eq!(legal_hints(&partner_hand), expected);
+        let plain = &partner_hand[..4];
+        assert_eq!(legal_hints(plain).iter().map(|(hint, _)| *hint).collect::<Vec<_>>(),
+            vec![Move::HintColor(Color::Red), Move::HintColor(Color::Green), Move::HintValue(1), Move::HintValue(2), Move::HintValue(3)]);
+    }
+
+    // two slots hinted as red and as 1 are both known red 1s, either can go as long as the other stays
+    #[test]
+    fn two_known_red_ones_are_duplicates() {
+        let mut knowledge = vec![DeckSubset::new_full(); 5];
+        apply_hint(&mut knowledge, &[0, 3], &Move::HintColor(Color::Red));
+        apply_hint(&mut knowledge, &[0, 3, 4], &Move::HintValue(1));
+        let resolved = resolved_hand(&knowledge, &DeckSubset::new_full());
+        assert_eq!(resolved[0], Some(Card::new(0)));
+        assert_eq!(resolved[3], Some(Card::new(0)));
+        assert!(is_known_duplicate(&resolved, 0, &[]));
+        assert!(is_known_duplicate(&resolved, 3, &[]));
+        assert!(!is_known_duplicate(&resolved, 4, &[]));
+        // a slot known alone is a duplicate once the partner holds the same kind
+        let alone = [resolved[0], None];
+        assert!(!is_known_duplicate(&alone, 0, &[Card::new(10)]));
+        assert!(is_known_duplicate(&alone, 0, &[Card::new(10), Card::new(2)]));
+    }
+
+    // late in the game: every red but the red 3s and every 5 but the blue one have been seen.
+    // the red slot, the 5 and the green 2 collapse, the untouched slots stay open
+    #[test]
+    fn resolved_hand_collapses_only_the_narrowed_slots() {
+        let full = DeckSubset::new_full();
+        let green_two = DeckSubset::from_color(Color::Green).intersect(&DeckSubset::from_value(2));
+        let knowledge = [DeckSubset::from_color(Color::Red), full, DeckSubset::from_value(5), full, green_two];
+        let mut unseen = full;
+        for card in full.iter_cards() {
+            let seen_red = card.get_color() == Color::Red && card.get_value() != 3;
+            let seen_five = card.get_value() == 5 && card.get_color() != Color::Blue;
+            if seen_red || seen_five { unseen.remove_card(&card); }
+        }
+        assert_eq!(resolved_hand(&knowledge, &unseen), vec![Some(Card::new(5)), None, Some(Card::new(29)), None, Some(Card::new(13))]);
+    }
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use hanabi::card::Card;
+use hanabi::deck::Deck;
+use hanabi::enums::{Move, MoveResult};
+use hanabi::game::{Game, GameConfig, GameView};
+use hanabi::player::Player;
+use hanabi::strategy::Strategy;
+
+// what a strategy was told, kept where the test can read it after the game took the strategy
+#[derive(Default)]
+struct Told {
+    own: Vec<(MoveResult, bool)>,
+    others: Vec<MoveResult>,
+    seen: Vec<Card>,
+}
+
+struct Recorder(Rc<RefCell<Told>>);
+
+impl Strategy for Recorder {
+    fn name(&self) -> &'static str { "Recorder" }
+    fn initialize(&mut self, _other_player_hands: &[Vec<Card>], _config: &GameConfig) {}
+    fn reset(&mut self) {}
+    fn decide_move(&mut self, _view: &GameView) -> Move { unreachable!("the moves are applied by the test") }
+
+    fn update_after_own_move(&mut self, _mv: &Move, mv_result: &MoveResult, got_new_card: bool) {
+        self.0.borrow_mut().own.push((mv_result.clone(), got_new_card));
+    }
+
+    fn update_after_other_player_move(&mut self, _player_offset: usize, _mv: &Move, mv_result: &MoveResult) {
+        self.0.borrow_mut().others.push(mv_result.clone());
+    }
+
+    fn see(&mut self, card: &Card) {
+        self.0.borrow_mut().seen.push(*card);
+    }
+}
+
+// the unshuffled deck deals the white 1 (card 41) into the last slot of player 1, the yellow 5 is drawn next.
+// the player who played learns that they drew, their partner learns what
+#[test]
+fn drawn_card_reaches_both_strategies() {
+    let told = [Rc::new(RefCell::new(Told::default())), Rc::new(RefCell::new(Told::default()))];
+    let players = told.iter().map(|t| Player::new(Box::new(Recorder(Rc::clone(t))))).collect();
+    let mut game = Game::new_with_deck(players, GameConfig::for_players(2), Deck::new_full_deck());
+
+    let result = game.apply_move(Move::Play(4)).expect("player 1 holds five cards");
+    let drawn = Card::new(39);
+    assert_eq!(result, MoveResult::Play { success: true, card: Card::new(41), drawn: Some(drawn) });
+    assert_eq!(game.players[0].hand.last(), Some(&drawn));
+
+    let mover = told[0].borrow();
+    assert_eq!(mover.own, vec![(MoveResult::Play { success: true, card: Card::new(41), drawn: None }, true)]);
+    assert!(mover.seen.is_empty());
+
+    let partner = told[1].borrow();
+    assert_eq!(partner.others, vec![result.clone()]);
+    assert_eq!(partner.seen, vec![drawn]);
+}
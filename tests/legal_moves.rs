@@ -0,0 +1,24 @@
+use hanabi::game::{Game, GameConfig};
+use hanabi::player::Player;
+use hanabi::strategies::{registry, INTERACTIVE};
+
+const SEEDS: u64 = 1000;
+
+// every registered bot plays seeded games with the next bot in the registry (the last with the first),
+// so each strategy is checked in both seats. the engine must accept every move they make
+#[test]
+fn strategies_only_make_legal_moves() {
+    let bots: Vec<_> = registry().into_iter().filter(|(name, _)| !INTERACTIVE.contains(name)).collect();
+    for (i, &(p1_name, p1)) in bots.iter().enumerate() {
+        let (p2_name, p2) = bots[(i + 1) % bots.len()];
+        for seed in 0..SEEDS {
+            let mut game = Game::new_seeded(vec![Player::new(p1()), Player::new(p2())], GameConfig::for_players(2), seed);
+            while game.game_over().is_none() {
+                let mv = game.decide_move();
+                if let Err(reason) = game.apply_move(mv) {
+                    panic!("{} vs {} with seed {}: {:?} was refused ({:?})", p1_name, p2_name, seed, mv, reason);
+                }
+            }
+        }
+    }
+}